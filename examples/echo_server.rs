@@ -0,0 +1,42 @@
+//! WebSocket echo server used by tests/socket.rs, start it before the wasm tests:
+//!
+//! cargo run --example echo_server
+//!
+//! Every text and binary message is sent back, the text "close" closes the connection
+//! and "silent" stops the answers on the connection. The address is 127.0.0.1:9001 or ECHO_SERVER_ADDRESS.
+
+#[cfg(not(target_arch = "wasm32"))]
+fn main() {
+    use std::net::TcpListener;
+    use tungstenite::Message;
+
+    let address = std::env::var("ECHO_SERVER_ADDRESS").unwrap_or_else(|_| "127.0.0.1:9001".to_owned());
+    let listener = TcpListener::bind(&address).expect("The address of the echo server is already used");
+    println!("echo server on ws://{address}");
+    for stream in listener.incoming().flatten() {
+        std::thread::spawn(move || {
+            let Ok(mut socket) = tungstenite::accept(stream) else { return };
+            let mut silent = false;
+            while let Ok(message) = socket.read() {
+                let echo = match &message {
+                    Message::Text(text) if text.as_str() == "close" => {
+                        let _ = socket.close(None);
+                        false
+                    }
+                    Message::Text(text) if text.as_str() == "silent" => {
+                        silent = true;
+                        false
+                    }
+                    Message::Text(_) | Message::Binary(_) => !silent,
+                    _ => false,
+                };
+                if echo && socket.send(message).is_err() {
+                    return;
+                }
+            }
+        });
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+fn main() {}
@@ -9,10 +9,21 @@ pub use utils::MinimalHtml;
 pub use utils::MinimalList;
 pub use utils::MinimalWindow;
 pub use utils::MinimalNode;
+pub use utils::listen;
+pub use utils::listen_passive;
+pub use utils::Listener;
+pub use utils::timeout;
+pub use utils::Timeout;
+pub use utils::interval;
+pub use utils::Interval;
+pub use utils::storage;
+pub use utils::cookies;
 pub mod utils {
     use wasm_bindgen::prelude::*;
-    use web_sys::{Location, Attr, Document, Element, HtmlElement, HtmlSlotElement, NodeList, Window, Node};
+    use web_sys::{Location, Attr, Document, Element, HtmlElement, HtmlSlotElement, NodeList, Window, Node, Event, EventTarget, AddEventListenerOptions};
     use std::panic;
+    use crate::animation::{AnimationHandle, Keyframe, Timing};
+    use crate::scroll::ScrollOptions;
 
     //error
     #[wasm_bindgen]
@@ -120,7 +131,137 @@ pub mod utils {
             Closure::<dyn Fn($e)>::new($body)
         };
     }
-    
+
+    /// An event listener that is removed when dropped.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let listener = listen(&window(), "resize", move |_e| {
+    ///     gloo::console::log!("resized");
+    /// });
+    /// //the listener lives until `listener` is dropped, call forget() to keep it forever
+    /// listener.forget();
+    /// ```
+    ///
+    pub struct Listener {
+        target: EventTarget,
+        event: String,
+        closure: Option<Closure<dyn FnMut(Event)>>,
+    }
+
+    /// Add an event listener to target, it's removed when the returned Listener is dropped.
+    pub fn listen<F>(target: &EventTarget, event: &str, callback: F) -> Listener
+    where
+        F: FnMut(Event) + 'static,
+    {
+        let closure = Closure::<dyn FnMut(Event)>::new(callback);
+        target
+            .add_event_listener_with_callback(event, closure.as_ref().unchecked_ref())
+            .custom_expect("It's not possible add the listener for : ".to_owned() + event);
+        Listener {
+            target: target.clone(),
+            event: event.to_owned(),
+            closure: Some(closure),
+        }
+    }
+
+    /// Same as listen() with a passive listener, for scroll, touch and wheel events that never call prevent_default().
+    pub fn listen_passive<F>(target: &EventTarget, event: &str, callback: F) -> Listener
+    where
+        F: FnMut(Event) + 'static,
+    {
+        let closure = Closure::<dyn FnMut(Event)>::new(callback);
+        let mut options = AddEventListenerOptions::new();
+        options.passive(true);
+        target
+            .add_event_listener_with_callback_and_add_event_listener_options(event, closure.as_ref().unchecked_ref(), &options)
+            .custom_expect("It's not possible add the listener for : ".to_owned() + event);
+        Listener {
+            target: target.clone(),
+            event: event.to_owned(),
+            closure: Some(closure),
+        }
+    }
+
+    impl Listener {
+        /// Keep the listener forever, it will never be removed.
+        pub fn forget(mut self) {
+            if let Some(closure) = self.closure.take() {
+                closure.forget();
+            }
+        }
+    }
+
+    impl Drop for Listener {
+        fn drop(&mut self) {
+            if let Some(closure) = self.closure.take() {
+                let _ = self
+                    .target
+                    .remove_event_listener_with_callback(&self.event, closure.as_ref().unchecked_ref());
+            }
+        }
+    }
+
+    /// A setTimeout that is cleared when dropped.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let hide = timeout(3000, move || toast.add_class("hidden"));
+    /// ```
+    pub struct Timeout {
+        id: i32,
+        _closure: Closure<dyn FnMut()>,
+    }
+
+    /// Call a function after millis milliseconds, unless the returned Timeout is dropped before.
+    pub fn timeout<F>(millis: u32, callback: F) -> Timeout
+    where
+        F: FnOnce() + 'static,
+    {
+        let mut callback = Some(callback);
+        let closure = Closure::<dyn FnMut()>::new(move || {
+            if let Some(callback) = callback.take() {
+                callback();
+            }
+        });
+        let id = window()
+            .set_timeout_with_callback_and_timeout_and_arguments_0(closure.as_ref().unchecked_ref(), millis as i32)
+            .custom_expect("It's not possible set the timeout".to_owned());
+        Timeout { id, _closure: closure }
+    }
+
+    impl Drop for Timeout {
+        fn drop(&mut self) {
+            window().clear_timeout_with_handle(self.id);
+        }
+    }
+
+    /// A setInterval that is cleared when dropped.
+    pub struct Interval {
+        id: i32,
+        _closure: Closure<dyn FnMut()>,
+    }
+
+    /// Call a function every millis milliseconds until the returned Interval is dropped.
+    pub fn interval<F>(millis: u32, callback: F) -> Interval
+    where
+        F: FnMut() + 'static,
+    {
+        let closure = Closure::<dyn FnMut()>::new(callback);
+        let id = window()
+            .set_interval_with_callback_and_timeout_and_arguments_0(closure.as_ref().unchecked_ref(), millis as i32)
+            .custom_expect("It's not possible set the interval".to_owned());
+        Interval { id, _closure: closure }
+    }
+
+    impl Drop for Interval {
+        fn drop(&mut self) {
+            window().clear_interval_with_handle(self.id);
+        }
+    }
+
     /// Create a Window.
     ///
     /// # Examples
@@ -150,6 +291,702 @@ pub mod utils {
         web_sys::window().expect("no window found").document_page()
     }
 
+    /// Read and write the cookies of document.cookie, the parsing and the serialization are pure functions.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use minimal_web::cookies::{self, CookieOptions, SameSite};
+    ///
+    /// cookies::set("consent", "analytics", CookieOptions {
+    ///     max_age: Some(60 * 60 * 24 * 365),
+    ///     same_site: Some(SameSite::Lax),
+    ///     ..Default::default()
+    /// });
+    /// assert_eq!(cookies::get("consent"), Some("analytics".to_owned()));
+    /// ```
+    pub mod cookies {
+        use wasm_bindgen::JsCast;
+        use super::{document, ResultExt};
+
+        /// SameSite attribute of a cookie.
+        #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+        pub enum SameSite {
+            Strict,
+            Lax,
+            None,
+        }
+
+        impl SameSite {
+            pub fn as_str(&self) -> &'static str {
+                match self {
+                    SameSite::Strict => "Strict",
+                    SameSite::Lax => "Lax",
+                    SameSite::None => "None",
+                }
+            }
+        }
+
+        /// Attributes of a cookie, path is "/" by default.
+        #[derive(Clone, Debug, PartialEq, Eq)]
+        pub struct CookieOptions {
+            /// Seconds before it expires, 0 or less removes it.
+            pub max_age: Option<i64>,
+            /// Expiry in milliseconds since 1970, like js_sys::Date::now().
+            pub expires: Option<i64>,
+            pub path: Option<String>,
+            pub domain: Option<String>,
+            pub secure: bool,
+            pub same_site: Option<SameSite>,
+        }
+
+        impl Default for CookieOptions {
+            fn default() -> Self {
+                CookieOptions {
+                    max_age: None,
+                    expires: None,
+                    path: Some("/".to_owned()),
+                    domain: None,
+                    secure: false,
+                    same_site: None,
+                }
+            }
+        }
+
+        //characters allowed by RFC 6265, % is encoded too so decode is always right
+        fn is_value_char(byte: u8) -> bool {
+            byte.is_ascii_alphanumeric() || b"!#$&'()*+-./:<=>?@[]^_`{|}~".contains(&byte)
+        }
+
+        //a cookie name is a token of RFC 7230
+        fn is_name_char(byte: u8) -> bool {
+            byte.is_ascii_alphanumeric() || b"!#$&'*+-.^_`|~".contains(&byte)
+        }
+
+        //path and domain can have any character but controls and ';', they are encoded so they can't add attributes
+        //% is kept, a path like /a%20b is already encoded
+        fn is_attribute_char(byte: u8) -> bool {
+            (0x20..0x7F).contains(&byte) && byte != b';'
+        }
+
+        fn encode_with(text: &str, allowed: fn(u8) -> bool) -> String {
+            let mut encoded = String::with_capacity(text.len());
+            for byte in text.bytes() {
+                if allowed(byte) {
+                    encoded.push(byte as char);
+                } else {
+                    encoded.push_str(&format!("%{byte:02X}"));
+                }
+            }
+            encoded
+        }
+
+        /// Percent-encode a cookie value, UTF-8 characters are encoded byte by byte.
+        pub fn encode(value: &str) -> String {
+            encode_with(value, is_value_char)
+        }
+
+        /// Decode a percent-encoded text, invalid sequences are kept as they are.
+        pub fn decode(text: &str) -> String {
+            let bytes = text.as_bytes();
+            let mut decoded = Vec::with_capacity(bytes.len());
+            let mut i = 0;
+            while i < bytes.len() {
+                let hex = bytes.get(i + 1..i + 3).and_then(|h| std::str::from_utf8(h).ok()).and_then(|h| u8::from_str_radix(h, 16).ok());
+                match (bytes[i], hex) {
+                    (b'%', Some(byte)) => {
+                        decoded.push(byte);
+                        i += 3;
+                    }
+                    (byte, _) => {
+                        decoded.push(byte);
+                        i += 1;
+                    }
+                }
+            }
+            String::from_utf8_lossy(&decoded).into_owned()
+        }
+
+        /// Parse a cookie string like document.cookie, names and values are decoded.
+        ///
+        /// # Examples
+        ///
+        /// ```ignore
+        /// assert_eq!(parse("a=1; b=hello%20world"), vec![("a".to_owned(), "1".to_owned()), ("b".to_owned(), "hello world".to_owned())]);
+        /// ```
+        pub fn parse(cookies: &str) -> Vec<(String, String)> {
+            cookies
+                .split(';')
+                .filter_map(|pair| {
+                    let (name, value) = pair.split_once('=')?;
+                    let name = name.trim();
+                    if name.is_empty() {
+                        return None;
+                    }
+                    let value = value.trim();
+                    let value = value.strip_prefix('"').and_then(|v| v.strip_suffix('"')).unwrap_or(value);
+                    Some((decode(name), decode(value)))
+                })
+                .collect()
+        }
+
+        /// Format milliseconds since 1970 as an HTTP date, for example "Thu, 01 Jan 1970 00:00:00 GMT".
+        pub fn http_date(millis: i64) -> String {
+            const DAYS: [&str; 7] = ["Thu", "Fri", "Sat", "Sun", "Mon", "Tue", "Wed"];
+            const MONTHS: [&str; 12] = ["Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec"];
+            let seconds = millis.div_euclid(1000);
+            let (days, time) = (seconds.div_euclid(86400), seconds.rem_euclid(86400));
+            //civil from days, http://howardhinnant.github.io/date_algorithms.html
+            let z = days + 719468;
+            let era = z.div_euclid(146097);
+            let doe = z.rem_euclid(146097);
+            let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+            let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+            let mp = (5 * doy + 2) / 153;
+            let day = doy - (153 * mp + 2) / 5 + 1;
+            let month = if mp < 10 { mp + 3 } else { mp - 9 };
+            let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+            format!(
+                "{}, {:02} {} {} {:02}:{:02}:{:02} GMT",
+                DAYS[days.rem_euclid(7) as usize],
+                day,
+                MONTHS[(month - 1) as usize],
+                year,
+                time / 3600,
+                time % 3600 / 60,
+                time % 60
+            )
+        }
+
+        /// Build the string assigned to document.cookie, name and value are encoded.
+        /// ';' and control characters of path and domain are percent-encoded, the rest is kept as it is.
+        ///
+        /// # Examples
+        ///
+        /// ```ignore
+        /// let options = CookieOptions { max_age: Some(3600), secure: true, ..Default::default() };
+        /// assert_eq!(serialize("theme", "dark mode", &options), "theme=dark%20mode; Max-Age=3600; Path=/; Secure");
+        /// ```
+        pub fn serialize(name: &str, value: &str, options: &CookieOptions) -> String {
+            let mut cookie = format!("{}={}", encode_with(name, is_name_char), encode(value));
+            if let Some(max_age) = options.max_age {
+                cookie.push_str(&format!("; Max-Age={max_age}"));
+            }
+            if let Some(expires) = options.expires {
+                cookie.push_str(&format!("; Expires={}", http_date(expires)));
+            }
+            if let Some(path) = &options.path {
+                cookie.push_str(&format!("; Path={}", encode_with(path, is_attribute_char)));
+            }
+            if let Some(domain) = &options.domain {
+                cookie.push_str(&format!("; Domain={}", encode_with(domain, is_attribute_char)));
+            }
+            //browsers reject SameSite=None without Secure
+            if options.secure || options.same_site == Some(SameSite::None) {
+                cookie.push_str("; Secure");
+            }
+            if let Some(same_site) = options.same_site {
+                cookie.push_str(&format!("; SameSite={}", same_site.as_str()));
+            }
+            cookie
+        }
+
+        fn html_document() -> web_sys::HtmlDocument {
+            document().unchecked_into::<web_sys::HtmlDocument>()
+        }
+
+        /// Get every cookie of the page, in the order of document.cookie.
+        pub fn all() -> Vec<(String, String)> {
+            parse(&html_document().cookie().unwrap_or_default())
+        }
+
+        /// Get a cookie of the page.
+        pub fn get(name: &str) -> Option<String> {
+            all().into_iter().find(|(n, _)| n == name).map(|(_, value)| value)
+        }
+
+        /// Save a cookie of the page.
+        pub fn set(name: &str, value: &str, options: CookieOptions) {
+            html_document()
+                .set_cookie(&serialize(name, value, &options))
+                .custom_expect("It's not possible set the cookie : ".to_owned() + name);
+        }
+
+        /// Remove a cookie saved with path "/" and no domain.
+        pub fn remove(name: &str) {
+            remove_with(name, CookieOptions::default());
+        }
+
+        /// Remove a cookie, path and domain must be the ones used to save it.
+        pub fn remove_with(name: &str, options: CookieOptions) {
+            set(name, "", CookieOptions { max_age: Some(0), expires: Some(0), ..options });
+        }
+
+        #[cfg(test)]
+        mod tests {
+            use super::*;
+
+            #[test]
+            fn encode_decode_round_trip() {
+                for value in ["hello world", "a;b=c,d", "100%", "%25", "héllo ✓ 日本", ""] {
+                    assert_eq!(decode(&encode(value)), value);
+                }
+                assert_eq!(encode("a b;%"), "a%20b%3B%25");
+                assert_eq!(encode("é"), "%C3%A9");
+            }
+
+            #[test]
+            fn decode_keeps_invalid_sequences() {
+                assert_eq!(decode("%zz"), "%zz");
+                assert_eq!(decode("100%"), "100%");
+                assert_eq!(decode("%4"), "%4");
+                assert_eq!(decode("a%20%zzb"), "a %zzb");
+            }
+
+            #[test]
+            fn parse_cookies() {
+                let parsed = parse(r#"q="hi"; =x; flag; a=1; b=hello%20world; empty="#);
+                let expected = [("q", "hi"), ("a", "1"), ("b", "hello world"), ("empty", "")];
+                assert_eq!(parsed, expected.map(|(n, v)| (n.to_owned(), v.to_owned())));
+                assert!(parse("").is_empty());
+            }
+
+            #[test]
+            fn http_dates() {
+                assert_eq!(http_date(0), "Thu, 01 Jan 1970 00:00:00 GMT");
+                assert_eq!(http_date(1_709_164_800_000), "Thu, 29 Feb 2024 00:00:00 GMT");
+                assert_eq!(http_date(951_782_400_000 + 86_399_999), "Tue, 29 Feb 2000 23:59:59 GMT");
+                assert_eq!(http_date(-1000), "Wed, 31 Dec 1969 23:59:59 GMT");
+            }
+
+            #[test]
+            fn serialize_attributes() {
+                let options = CookieOptions {
+                    max_age: Some(3600),
+                    expires: Some(0),
+                    path: Some("/app".to_owned()),
+                    domain: Some("example.com".to_owned()),
+                    secure: true,
+                    same_site: Some(SameSite::Lax),
+                };
+                assert_eq!(
+                    serialize("theme", "dark mode", &options),
+                    "theme=dark%20mode; Max-Age=3600; Expires=Thu, 01 Jan 1970 00:00:00 GMT; Path=/app; Domain=example.com; Secure; SameSite=Lax"
+                );
+                assert_eq!(serialize("a b", "1", &CookieOptions::default()), "a%20b=1; Path=/");
+            }
+
+            #[test]
+            fn same_site_none_is_secure() {
+                let options = CookieOptions { same_site: Some(SameSite::None), ..Default::default() };
+                assert_eq!(serialize("a", "1", &options), "a=1; Path=/; Secure; SameSite=None");
+            }
+
+            #[test]
+            fn serialize_encodes_attribute_injection() {
+                let options = CookieOptions {
+                    path: Some("/; Domain=evil.com".to_owned()),
+                    domain: Some("example.com; Secure\n".to_owned()),
+                    ..Default::default()
+                };
+                let cookie = serialize("a", "1", &options);
+                assert_eq!(cookie, "a=1; Path=/%3B Domain=evil.com; Domain=example.com%3B Secure%0A");
+                assert_eq!(cookie.matches(';').count(), 2);
+            }
+
+            #[test]
+            fn serialize_keeps_encoded_paths() {
+                let options = CookieOptions { path: Some("/a%20b".to_owned()), ..Default::default() };
+                assert_eq!(serialize("a", "1", &options), "a=1; Path=/a%20b");
+            }
+        }
+    }
+
+    /// Typed localStorage and sessionStorage, values are saved as JSON with serde.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use minimal_web::storage;
+    ///
+    /// let cart = storage::local().namespace("shop");
+    /// cart.set("items", &vec![1, 2, 3])?;
+    /// let items: Option<Vec<u32>> = cart.get("items")?;
+    /// ```
+    pub mod storage {
+        use std::fmt;
+        use std::ops::Deref;
+        use std::rc::Rc;
+        use serde::de::DeserializeOwned;
+        use serde::Serialize;
+        use wasm_bindgen::prelude::*;
+        use yew::prelude::*;
+        use super::{listen, window, Listener};
+
+        /// CustomEvent dispatched on window when a Storage of this page changes, the storage event is only for the other tabs.
+        /// The detail is `{ area: "local" | "session", key: full key or null if cleared, prefix: namespace cleared }`.
+        pub const STORAGE_EVENT: &str = "minimal-storage-change";
+        //tag of the wrapper of the values saved with set_with_ttl, {"__minimal": "ttl", "expires": ms, "value": v}
+        const TAG: &str = "__minimal";
+        const TTL: &str = "ttl";
+
+        /// Error of a Storage operation.
+        #[derive(Clone, Debug, PartialEq)]
+        pub enum StorageError {
+            /// The storage is disabled, for example in private mode.
+            Unavailable,
+            /// There's no more space for the value.
+            QuotaExceeded,
+            /// The saved value is not valid JSON for the type.
+            Parse(String),
+            /// The value can't be converted to JSON.
+            Serialize(String),
+            /// Any other error of the browser.
+            Js(String),
+        }
+
+        impl fmt::Display for StorageError {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                match self {
+                    StorageError::Unavailable => write!(f, "the storage is not available"),
+                    StorageError::QuotaExceeded => write!(f, "the storage quota is exceeded"),
+                    StorageError::Parse(e) => write!(f, "the saved value can't be parsed: {e}"),
+                    StorageError::Serialize(e) => write!(f, "the value can't be serialized: {e}"),
+                    StorageError::Js(e) => write!(f, "storage error: {e}"),
+                }
+            }
+        }
+
+        impl std::error::Error for StorageError {}
+
+        impl From<JsValue> for StorageError {
+            fn from(error: JsValue) -> Self {
+                let name = js_sys::Reflect::get(&error, &"name".into()).ok().and_then(|n| n.as_string());
+                match name.as_deref() {
+                    Some("QuotaExceededError") | Some("NS_ERROR_DOM_QUOTA_REACHED") => StorageError::QuotaExceeded,
+                    _ => StorageError::Js(format!("{error:?}")),
+                }
+            }
+        }
+
+        /// Which Web Storage is used.
+        #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+        pub enum StorageKind {
+            Local,
+            Session,
+        }
+
+        /// A localStorage or sessionStorage, optionally in a namespace, cheap to clone.
+        #[derive(Clone, Debug)]
+        pub struct Storage {
+            raw: Option<web_sys::Storage>,
+            kind: StorageKind,
+            prefix: String,
+        }
+
+        /// Get localStorage, every operation returns StorageError::Unavailable if it's disabled.
+        pub fn local() -> Storage {
+            Storage {
+                raw: window().local_storage().ok().flatten(),
+                kind: StorageKind::Local,
+                prefix: String::new(),
+            }
+        }
+
+        /// Get sessionStorage, every operation returns StorageError::Unavailable if it's disabled.
+        pub fn session() -> Storage {
+            Storage {
+                raw: window().session_storage().ok().flatten(),
+                kind: StorageKind::Session,
+                prefix: String::new(),
+            }
+        }
+
+        /// Change of a key, from this tab or from another one.
+        #[derive(Clone, Debug, PartialEq)]
+        pub struct StorageChange {
+            /// Key without the namespace, None if the storage was cleared.
+            pub key: Option<String>,
+        }
+
+        fn now() -> f64 {
+            js_sys::Date::now()
+        }
+
+        impl Storage {
+            /// Get the same storage where every key starts with "name:", namespaces can be nested.
+            pub fn namespace(&self, name: &str) -> Storage {
+                Storage {
+                    prefix: format!("{}{name}:", self.prefix),
+                    ..self.clone()
+                }
+            }
+
+            /// Get the kind of the storage.
+            pub fn kind(&self) -> StorageKind {
+                self.kind
+            }
+
+            fn raw(&self) -> Result<&web_sys::Storage, StorageError> {
+                self.raw.as_ref().ok_or(StorageError::Unavailable)
+            }
+
+            fn area(&self) -> &'static str {
+                match self.kind {
+                    StorageKind::Local => "local",
+                    StorageKind::Session => "session",
+                }
+            }
+
+            //key is the full key, None if the namespace was cleared
+            fn notify(&self, key: Option<&str>) {
+                let detail = js_sys::Object::new();
+                let _ = js_sys::Reflect::set(&detail, &"area".into(), &self.area().into());
+                let _ = js_sys::Reflect::set(&detail, &"key".into(), &key.map(JsValue::from).unwrap_or(JsValue::NULL));
+                let _ = js_sys::Reflect::set(&detail, &"prefix".into(), &self.prefix.as_str().into());
+                let mut init = web_sys::CustomEventInit::new();
+                init.detail(&detail);
+                if let Ok(event) = web_sys::CustomEvent::new_with_event_init_dict(STORAGE_EVENT, &init) {
+                    let _ = window().dispatch_event(&event);
+                }
+            }
+
+            //the value saved by set_with_ttl, None if it's not a wrapper
+            fn unwrap_ttl(value: &mut serde_json::Value) -> Option<(f64, serde_json::Value)> {
+                let wrapper = value.as_object_mut()?;
+                if wrapper.len() != 3 || wrapper.get(TAG)?.as_str()? != TTL {
+                    return None;
+                }
+                let expires = wrapper.get("expires")?.as_f64()?;
+                Some((expires, wrapper.get_mut("value")?.take()))
+            }
+
+            /// Get a value, None if it's missing or expired.
+            pub fn get<T: DeserializeOwned>(&self, key: &str) -> Result<Option<T>, StorageError> {
+                let full = format!("{}{key}", self.prefix);
+                let Some(text) = self.raw()?.get_item(&full)? else { return Ok(None) };
+                let mut value: serde_json::Value = serde_json::from_str(&text).map_err(|e| StorageError::Parse(e.to_string()))?;
+                if let Some((expires, inner)) = Self::unwrap_ttl(&mut value) {
+                    if expires <= now() {
+                        self.raw()?.remove_item(&full)?;
+                        return Ok(None);
+                    }
+                    value = inner;
+                }
+                serde_json::from_value(value).map(Some).map_err(|e| StorageError::Parse(e.to_string()))
+            }
+
+            /// Save a value as JSON.
+            pub fn set<T: Serialize + ?Sized>(&self, key: &str, value: &T) -> Result<(), StorageError> {
+                let text = serde_json::to_string(value).map_err(|e| StorageError::Serialize(e.to_string()))?;
+                let full = format!("{}{key}", self.prefix);
+                self.raw()?.set_item(&full, &text)?;
+                self.notify(Some(&full));
+                Ok(())
+            }
+
+            /// Save a value that expires after ttl milliseconds.
+            pub fn set_with_ttl<T: Serialize + ?Sized>(&self, key: &str, value: &T, ttl: f64) -> Result<(), StorageError> {
+                let value = serde_json::to_value(value).map_err(|e| StorageError::Serialize(e.to_string()))?;
+                let mut wrapper = serde_json::Map::new();
+                wrapper.insert(TAG.to_owned(), TTL.into());
+                wrapper.insert("expires".to_owned(), (now() + ttl).into());
+                wrapper.insert("value".to_owned(), value);
+                self.set(key, &wrapper)
+            }
+
+            /// Remove a value.
+            pub fn remove(&self, key: &str) -> Result<(), StorageError> {
+                let full = format!("{}{key}", self.prefix);
+                self.raw()?.remove_item(&full)?;
+                self.notify(Some(&full));
+                Ok(())
+            }
+
+            /// Get every key of the namespace, without the namespace.
+            pub fn keys(&self) -> Result<Vec<String>, StorageError> {
+                let raw = self.raw()?;
+                let length = raw.length()?;
+                let mut keys = Vec::new();
+                for i in 0..length {
+                    if let Some(key) = raw.key(i)? {
+                        if let Some(key) = key.strip_prefix(&self.prefix) {
+                            keys.push(key.to_owned());
+                        }
+                    }
+                }
+                Ok(keys)
+            }
+
+            /// Remove every key of the namespace, the whole storage without a namespace.
+            pub fn clear(&self) -> Result<(), StorageError> {
+                if self.prefix.is_empty() {
+                    self.raw()?.clear()?;
+                } else {
+                    for key in self.keys()? {
+                        self.raw()?.remove_item(&format!("{}{key}", self.prefix))?;
+                    }
+                }
+                self.notify(None);
+                Ok(())
+            }
+
+            /// Call a function when a key of the namespace changes, in this tab or in another one.
+            /// The change has key None when the storage or a namespace containing this one is cleared.
+            pub fn on_change<F>(&self, callback: F) -> (Listener, Listener)
+            where
+                F: Fn(StorageChange) + 'static,
+            {
+                let callback = Rc::new(callback);
+                let storage = self.clone();
+                let other = callback.clone();
+                let other_tabs = listen(&window(), "storage", move |e| {
+                    let Some(e) = e.dyn_ref::<web_sys::StorageEvent>() else { return };
+                    let same_area = match (e.storage_area(), storage.raw.as_ref()) {
+                        (Some(area), Some(raw)) => area == *raw,
+                        _ => false,
+                    };
+                    if !same_area {
+                        return;
+                    }
+                    match e.key() {
+                        Some(key) => {
+                            if let Some(key) = key.strip_prefix(&storage.prefix) {
+                                other(StorageChange { key: Some(key.to_owned()) });
+                            }
+                        }
+                        None => other(StorageChange { key: None }),
+                    }
+                });
+                let storage = self.clone();
+                let this_tab = listen(&window(), STORAGE_EVENT, move |e| {
+                    let Some(detail) = e.dyn_ref::<web_sys::CustomEvent>().map(|e| e.detail()) else { return };
+                    let field = |name: &str| js_sys::Reflect::get(&detail, &name.into()).ok().and_then(|v| v.as_string());
+                    if field("area").as_deref() != Some(storage.area()) {
+                        return;
+                    }
+                    match field("key") {
+                        Some(key) => {
+                            if let Some(key) = key.strip_prefix(&storage.prefix) {
+                                callback(StorageChange { key: Some(key.to_owned()) });
+                            }
+                        }
+                        None => {
+                            let prefix = field("prefix").unwrap_or_default();
+                            if storage.prefix.starts_with(&prefix) || prefix.starts_with(&storage.prefix) {
+                                callback(StorageChange { key: None });
+                            }
+                        }
+                    }
+                });
+                (other_tabs, this_tab)
+            }
+        }
+
+        /// Handle returned by use_local_storage, it derefs to the value.
+        pub struct UseStorageHandle<T> {
+            value: Rc<T>,
+            key: Rc<str>,
+            default: Rc<T>,
+            state: UseStateHandle<Rc<T>>,
+        }
+
+        impl<T> Clone for UseStorageHandle<T> {
+            fn clone(&self) -> Self {
+                UseStorageHandle {
+                    value: self.value.clone(),
+                    key: self.key.clone(),
+                    default: self.default.clone(),
+                    state: self.state.clone(),
+                }
+            }
+        }
+
+        impl<T> Deref for UseStorageHandle<T> {
+            type Target = T;
+            fn deref(&self) -> &T {
+                &self.value
+            }
+        }
+
+        impl<T: PartialEq> PartialEq for UseStorageHandle<T> {
+            fn eq(&self, other: &Self) -> bool {
+                self.key == other.key && self.value == other.value
+            }
+        }
+
+        impl<T: Serialize> UseStorageHandle<T> {
+            /// Save a new value, every use_local_storage with the same key is updated.
+            pub fn set(&self, value: T) -> Result<(), StorageError> {
+                local().set(&self.key, &value)?;
+                self.state.set(Rc::new(value));
+                Ok(())
+            }
+
+            /// Remove the value, the default is used again.
+            pub fn remove(&self) -> Result<(), StorageError> {
+                local().remove(&self.key)?;
+                self.state.set(self.default.clone());
+                Ok(())
+            }
+        }
+
+        /// Get a value of localStorage, default if it's missing or not valid.
+        /// The component is updated when the value changes, also from another tab.
+        ///
+        /// # Examples
+        ///
+        /// ```ignore
+        /// let count = use_local_storage::<u32>("count", 0);
+        /// let onclick = { let count = count.clone(); Callback::from(move |_| { let _ = count.set(*count + 1); }) };
+        /// html!{ <button {onclick}>{ *count }</button> }
+        /// ```
+        #[hook]
+        pub fn use_local_storage<T>(key: &str, default: T) -> UseStorageHandle<T>
+        where
+            T: Serialize + DeserializeOwned + 'static,
+        {
+            let key: Rc<str> = Rc::from(key);
+            let default = use_mut_ref(|| Rc::new(default)).borrow().clone();
+            let read = {
+                let default = default.clone();
+                move |key: &str| local().get::<T>(key).ok().flatten().map(Rc::new).unwrap_or_else(|| default.clone())
+            };
+            let state = {
+                let key = key.clone();
+                let read = read.clone();
+                use_state(move || read(&key))
+            };
+            //key of the value in state, it's read again only when the key changes
+            let read_key = use_mut_ref(|| key.clone());
+            {
+                let state = state.clone();
+                use_effect_with_deps(
+                    move |key| {
+                        let key = key.clone();
+                        if *read_key.borrow() != key {
+                            *read_key.borrow_mut() = key.clone();
+                            state.set(read(&key));
+                        }
+                        let listeners = local().on_change(move |change| {
+                            if change.key.as_deref().map(|k| k == &*key).unwrap_or(true) {
+                                state.set(read(&key));
+                            }
+                        });
+                        move || drop(listeners)
+                    },
+                    key.clone(),
+                );
+            }
+            UseStorageHandle {
+                value: (*state).clone(),
+                key,
+                default,
+                state,
+            }
+        }
+    }
+
     /// Some Function for a Window element.
     ///
     /// # Examples
@@ -175,9 +1012,9 @@ pub mod utils {
         fn get_height(&self) -> f64;
         /// Get name of window, same as name() Function.
         fn get_name(&self) -> String;
-        /// Get scroll_x of window, same as scroll_x() Function.
+        /// Get scroll_x of window, same as scroll_x() Function, 0 if it's not available.
         fn get_scroll_x(&self) -> f64;
-        /// Get scroll_y of window, same as scroll_y() Function.
+        /// Get scroll_y of window, same as scroll_y() Function, 0 if it's not available.
         fn get_scroll_y(&self) -> f64;
         /// Get page_x_offset of window, same as page_x_offset() Function.
         fn get_page_x_offset(&self) -> f64;
@@ -191,6 +1028,8 @@ pub mod utils {
         fn get_outer_width(&self) -> JsValue;
         /// Get outer_height of window, same as outer_height() Function.
         fn get_outer_height(&self) -> JsValue;
+        /// Scroll smoothly to element, see scroll::smooth_scroll_to.
+        fn smooth_scroll_to(&self, element: &Element, options: ScrollOptions);
     }
 
     /// Some Function for a Document element.
@@ -245,6 +1084,8 @@ pub mod utils {
         fn create_el_ns(&self, namespace: &str, qualified_name: &str) -> Element;
         /// Create Element as HtmlElement, same as create_element.
         fn create_html_ns(&self, namespace: &str, qualified_name: &str) -> HtmlElement;
+        /// Get a cookie of document, see cookies::get.
+        fn get_cookie(&self, name: &str) -> Option<String>;
     }
 
     /// Some Function for a Element element.
@@ -370,6 +1211,8 @@ pub mod utils {
         fn set_css(&self, value: &str);
         /// Get css text, same as css_text().
         fn get_css(&self) -> String;
+        /// Start a keyframe animation with the Web Animations API, same as animate().
+        fn animate_keyframes(&self, keyframes: &[Keyframe], timing: Timing) -> AnimationHandle;
     }
 
     /// Some Function for a NodeList element.
@@ -452,10 +1295,10 @@ pub mod utils {
             self.name().custom_expect("There's not a name of window.".to_owned())
         }
         fn get_scroll_x(&self) -> f64 {
-            self.scroll_x().custom_expect("There's not a scroll_x of window.".to_owned())
+            self.scroll_x().unwrap_or(0.0)
         }
         fn get_scroll_y(&self) -> f64 {
-            self.scroll_y().custom_expect("There's not a scroll_y of window.".to_owned())
+            self.scroll_y().unwrap_or(0.0)
         }
         fn get_page_x_offset(&self) -> f64 {
             self.page_x_offset().custom_expect("There's not a page_x_offset of window.".to_owned())
@@ -475,6 +1318,9 @@ pub mod utils {
         fn get_outer_width(&self) -> JsValue {
             self.outer_width().custom_expect("There's not an outer_width of window.".to_owned())
         }
+        fn smooth_scroll_to(&self, element: &Element, options: ScrollOptions) {
+            crate::scroll::smooth_scroll_to(element, options)
+        }
     }
 
     impl MinimalDocument for Document {
@@ -554,6 +1400,9 @@ pub mod utils {
         fn create_html_ns(&self, namespace: &str, qualified_name: &str) -> HtmlElement {
             self.create_element_ns(Some(namespace), qualified_name).custom_expect("There's been an error, can't create element".to_owned()).to_html()
         }
+        fn get_cookie(&self, name: &str) -> Option<String> {
+            cookies::get(name)
+        }
     }
 
     impl MinimalElement for Element {
@@ -739,6 +1588,9 @@ pub mod utils {
         fn get_css(&self) -> String{
             self.style().css_text()
         }
+        fn animate_keyframes(&self, keyframes: &[Keyframe], timing: Timing) -> AnimationHandle {
+            crate::animation::keyframes::animate_keyframes(self, keyframes, timing)
+        }
     }
 
     impl MinimalList for NodeList {
@@ -780,84 +1632,7751 @@ pub mod utils {
         }
     }
 }
-pub mod animation{
+pub mod style {
     use wasm_bindgen::prelude::*;
-    use yew::prelude::*;
-    use web_sys::HtmlElement;
+    use web_sys::{CssStyleSheet, Document, HtmlStyleElement};
     use crate::utils::*;
-    
-    #[hook]
-    pub fn use_parallax() {
-        let document = crate::document();
-        use_effect(move ||{
-            let parallax = crate::fnmut!(MouseEvent, move |e: web_sys::MouseEvent|{
-                let element = e.target().expect("failed").dyn_into::<HtmlElement>().expect("failed to convert");
-                //Check if element exist
-                if element.closest(".parallax-effect").unwrap().is_some() {
-                    //Animation calc
-                    let el_html = element.closest_html(".parallax-effect");
-                    let mut value_max = 2.0;
-                    if el_html.get_attribute("data-max").is_some(){
-                        value_max = el_html.get_attr("data-max").trim().parse::<f64>().expect("error");
-                    } else if el_html.get_attribute("style").is_none(){
-                        el_html.set_css("");
-                    }
-                    let rect = el_html.get_bounding_client_rect();
-                    let width = el_html.offset_width() as f64;
-                    let height = el_html.offset_height() as f64;
-                    let mouse_x = ((e.client_x() as f64) - rect.left()) - (width / 2.0);
-                    let mouse_y = ((e.client_y() as f64) - rect.top()) - (height / 2.0);
-                    let rotate_x = value_max*mouse_y/(height/2.0);
-                    let rotate_y = -value_max*mouse_x/(width/2.0);
-                    //Animate element
-                    el_html
-                    .set_css(&format!("{} transform: perspective(1000px) rotateX({rotate_x}deg) rotateY({rotate_y}deg)", el_html.get_attr("style").trim()));
-                    //Check if glow exist then animate
-                    if el_html.query_selector(".wrapper .glow").unwrap().is_some(){
-                        let glow = el_html.query_selector_html(".glow");
-                        if glow.get_attribute("style").is_none(){
-                            glow.set_css("");
-                        }
-                        glow
-                        .set_css(&format!("{} transform:translate({mouse_x}px,{mouse_y}px); opacity:1;", glow.get_attr("style").trim())); 
-                    }
-                }
-            });
-            let parallax_on_out = crate::fnv!(MouseEvent, move |e: web_sys::MouseEvent|{
-                let element = e.target().expect("failed").dyn_into::<HtmlElement>().expect("failed to convert");
-                //Check if element exist
-                if element.closest(".parallax-effect").unwrap().is_some(){
-                    let el_html = element.closest_html(".parallax-effect");
-                    if el_html.get_attribute("style").is_none(){
-                        el_html.set_css("");
-                    }
-                    //Reset element
-                    el_html
-                    .set_css(&format!("{} transform: perspective(1000px) rotateX(0deg) rotateY(0deg)", el_html.get_attr("style").trim()));
-                    //Check if glow exist then reset animation
-                    if el_html.query_selector(".wrapper .glow").unwrap().is_some(){
-                        let glow = el_html.query_selector_html(".glow");
-                        if glow.get_attribute("style").is_none(){
-                            glow.set_css("");
-                        }
-                        glow
-                        .set_css(&format!("{} transform:translate(0px,0px); opacity:0;", glow.get_attr("style").trim()));
-                    }
-                }
-            });
-            if Some(document.query_selector_list(".parallax-effect")).is_some(){
-                let all_element = document.query_selector_list(".parallax-effect");
-                for i in 0..all_element.length() {
-                    let element = all_element.get_html(i);
-                    element.set_onmousemove(parallax.as_ref().dyn_ref());
-                    element.set_onmouseleave(parallax_on_out.as_ref().dyn_ref());
-                }
-            }
 
-            || {
-                parallax.forget();
-                parallax_on_out.forget();
-            }
-        });
+    #[wasm_bindgen]
+    extern {
+        #[wasm_bindgen(extends = CssStyleSheet, js_name = CSSStyleSheet)]
+        type ConstructableSheet;
+
+        #[wasm_bindgen(constructor, js_class = "CSSStyleSheet", catch)]
+        fn new() -> Result<ConstructableSheet, JsValue>;
+
+        #[wasm_bindgen(method, catch, js_name = replaceSync)]
+        fn replace_sync(sheet: &ConstructableSheet, text: &str) -> Result<(), JsValue>;
     }
-}
\ No newline at end of file
+
+    fn adopted_style_sheets(document: &Document) -> js_sys::Array {
+        js_sys::Reflect::get(document, &"adoptedStyleSheets".into())
+            .ok()
+            .filter(js_sys::Array::is_array)
+            .custom_expect("This browser doesn't support adoptedStyleSheets".to_owned())
+            .unchecked_into::<js_sys::Array>()
+    }
+
+    fn set_adopted_style_sheets(document: &Document, sheets: &js_sys::Array) {
+        js_sys::Reflect::set(document, &"adoptedStyleSheets".into(), sheets)
+            .custom_expect("It's not possible set adoptedStyleSheets".to_owned());
+    }
+
+    /// Add a `<style>` with your css in `<head>`, if a style with the same id exists its text is replaced.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use minimal_web::style::inject_css;
+    ///
+    /// inject_css("card-style", ".card { border-radius: 8px; }");
+    /// //calling it again with the same id doesn't add a second <style>
+    /// inject_css("card-style", ".card { border-radius: 12px; }");
+    /// ```
+    pub fn inject_css(id: &str, css: &str) -> HtmlStyleElement {
+        let document = crate::document();
+        let style = match document.get_element_by_id(id) {
+            Some(e) => e
+                .dyn_into::<HtmlStyleElement>()
+                .custom_expect("There's an element with this id but it's not a <style> : ".to_owned() + id),
+            None => {
+                let style = document
+                    .create_el("style")
+                    .dyn_into::<HtmlStyleElement>()
+                    .custom_expect("It's not possible create a <style> element".to_owned());
+                style.set_id(id);
+                document
+                    .head()
+                    .custom_expect("There's not a head in document.".to_owned())
+                    .app_child(style.clone().into());
+                style
+            }
+        };
+        if style.text_content().as_deref() != Some(css) {
+            style.set_text_content(Some(css));
+        }
+        style
+    }
+
+    /// Remove a `<style>` added with inject_css, nothing happens if it doesn't exist.
+    pub fn remove_css(id: &str) {
+        if let Some(e) = crate::document().get_element_by_id(id) {
+            if e.dyn_ref::<HtmlStyleElement>().is_some() {
+                e.remove();
+            }
+        }
+    }
+
+    /// Check if a `<style>` with this id has already been injected.
+    pub fn has_css(id: &str) -> bool {
+        crate::document()
+            .get_element_by_id(id)
+            .is_some_and(|e| e.dyn_ref::<HtmlStyleElement>().is_some())
+    }
+
+    /// Create a constructable CSSStyleSheet and push it in document.adoptedStyleSheets.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use minimal_web::style::{adopt_css, insert_rule};
+    ///
+    /// let sheet = adopt_css(".btn { padding: 8px 16px; }");
+    /// insert_rule(&sheet, ".btn:hover { opacity: .8; }");
+    /// ```
+    pub fn adopt_css(css: &str) -> CssStyleSheet {
+        let document = crate::document();
+        let sheet = ConstructableSheet::new().custom_expect("This browser doesn't support constructable stylesheets".to_owned());
+        sheet
+            .replace_sync(css)
+            .custom_expect("It's not possible parse the css of the stylesheet".to_owned());
+        //adoptedStyleSheets is a frozen array on some browsers, so it's always copied
+        let sheets = adopted_style_sheets(&document).slice(0, u32::MAX);
+        sheets.push(&sheet);
+        set_adopted_style_sheets(&document, &sheets);
+        sheet.unchecked_into::<CssStyleSheet>()
+    }
+
+    /// Remove a stylesheet created with adopt_css from document.adoptedStyleSheets.
+    pub fn unadopt_css(sheet: &CssStyleSheet) {
+        let document = crate::document();
+        let kept = adopted_style_sheets(&document).filter(&mut |s, _, _| !js_sys::Object::is(&s, sheet));
+        set_adopted_style_sheets(&document, &kept);
+    }
+
+    /// Insert a rule at the end of a stylesheet, return the index of the rule.
+    pub fn insert_rule(sheet: &CssStyleSheet, rule: &str) -> u32 {
+        let length = sheet
+            .css_rules()
+            .custom_expect("It's not possible read the rules of the stylesheet".to_owned())
+            .length();
+        sheet
+            .insert_rule_with_index(rule, length)
+            .custom_expect("It's not possible insert this rule : ".to_owned() + rule)
+    }
+
+    /// Get a class name that is always the same for the same key, used by scoped_css!.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// assert_eq!(scope_class("card"), scope_class("card"));
+    /// assert_ne!(scope_class("card"), scope_class("button"));
+    /// ```
+    pub fn scope_class(key: &str) -> String {
+        //FNV-1a, stable between builds so the class doesn't change on every reload
+        let mut hash: u32 = 0x811c9dc5;
+        for byte in key.bytes() {
+            hash ^= byte as u32;
+            hash = hash.wrapping_mul(0x01000193);
+        }
+        format!("mw-{:08x}", hash)
+    }
+
+    /// Replace every `&` of the selectors with the selector of the scope class,
+    /// the `&` of declarations, strings, comments and `url()` are kept.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let css = scope_selectors("& { color: red; } & p { margin: 0; }", "mw-1a2b3c4d");
+    /// assert_eq!(".mw-1a2b3c4d { color: red; } .mw-1a2b3c4d p { margin: 0; }", css);
+    /// ```
+    pub fn scope_selectors(css: &str, class: &str) -> String {
+        let selector = format!(".{}", class);
+        let mut out = String::with_capacity(css.len());
+        //text since the last { } or ; and the positions of its & outside strings and comments
+        let mut segment = String::new();
+        let mut ampersands = Vec::new();
+        let mut depth = 0usize;
+        let mut chars = css.chars().peekable();
+        while let Some(c) = chars.next() {
+            match c {
+                '"' | '\'' => {
+                    segment.push(c);
+                    while let Some(s) = chars.next() {
+                        segment.push(s);
+                        if s == '\\' {
+                            segment.extend(chars.next());
+                        } else if s == c {
+                            break;
+                        }
+                    }
+                }
+                '/' if chars.peek() == Some(&'*') => {
+                    segment.push(c);
+                    let mut last = ' ';
+                    for s in chars.by_ref() {
+                        segment.push(s);
+                        if last == '*' && s == '/' {
+                            break;
+                        }
+                        last = s;
+                    }
+                }
+                '(' => {
+                    depth += 1;
+                    segment.push(c);
+                }
+                ')' => {
+                    depth = depth.saturating_sub(1);
+                    segment.push(c);
+                }
+                '&' => {
+                    ampersands.push(segment.len());
+                    segment.push(c);
+                }
+                '{' if depth == 0 => {
+                    //the segment is a prelude: a selector or an at-rule
+                    let mut last = 0;
+                    for &i in &ampersands {
+                        out.push_str(&segment[last..i]);
+                        out.push_str(&selector);
+                        last = i + 1;
+                    }
+                    out.push_str(&segment[last..]);
+                    out.push(c);
+                    segment.clear();
+                    ampersands.clear();
+                }
+                '}' | ';' if depth == 0 => {
+                    out.push_str(&segment);
+                    out.push(c);
+                    segment.clear();
+                    ampersands.clear();
+                }
+                _ => segment.push(c),
+            }
+        }
+        out.push_str(&segment);
+        out
+    }
+
+    /// Inject a scoped css and return its class, use scoped_css! instead of calling it directly.
+    pub fn inject_scoped(key: &str, css: &str) -> String {
+        let class = scope_class(key);
+        inject_css(&class, &scope_selectors(css, &class));
+        class
+    }
+
+    /// Easier way for create a scoped css, `&` is the element with the returned class.
+    /// Every call site has its own class, so you can call it on every render.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let card = scoped_css!("& { padding: 16px; } & h2 { font-size: 1.2rem; }");
+    /// html!{
+    ///     <div class={card}>
+    ///         <h2>{"Title"}</h2>
+    ///     </div>
+    /// }
+    /// ```
+    ///
+    #[macro_export]
+    macro_rules! scoped_css {
+        ($css:expr) => {
+            $crate::style::inject_scoped(concat!(module_path!(), ":", line!(), ":", column!()), $css)
+        };
+        ($key:expr, $css:expr) => {
+            $crate::style::inject_scoped($key, $css)
+        };
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn scope_selectors_replaces_selectors() {
+            assert_eq!(
+                scope_selectors("& { color: red; } & p, a > & { margin: 0; }", "mw-1"),
+                ".mw-1 { color: red; } .mw-1 p, a > .mw-1 { margin: 0; }"
+            );
+            assert_eq!(
+                scope_selectors("@media (min-width: 600px) { &:hover { opacity: .8; } }", "mw-1"),
+                "@media (min-width: 600px) { .mw-1:hover { opacity: .8; } }"
+            );
+        }
+
+        #[test]
+        fn scope_selectors_keeps_values() {
+            let css = "& { content: \"a & b\"; background: url(/img?a=1&b=2); } /* & */ &[title='x&y'] { }";
+            assert_eq!(
+                scope_selectors(css, "mw-1"),
+                ".mw-1 { content: \"a & b\"; background: url(/img?a=1&b=2); } /* & */ .mw-1[title='x&y'] { }"
+            );
+            assert_eq!(
+                scope_selectors("& { background: url(data:image/svg+xml;utf8,<svg>&amp;</svg>); }", "mw-1"),
+                ".mw-1 { background: url(data:image/svg+xml;utf8,<svg>&amp;</svg>); }"
+            );
+        }
+    }
+}
+pub mod theme {
+    use std::cell::Cell;
+    use web_sys::Event;
+    use yew::prelude::*;
+    use crate::media::try_media_query;
+    use crate::utils::*;
+
+    /// Key used for save the theme in localStorage.
+    pub const THEME_KEY: &str = "minimal-theme";
+    /// Event dispatched on window when the theme changes.
+    pub const THEME_EVENT: &str = "minimal-theme-change";
+    const DARK_QUERY: &str = "(prefers-color-scheme: dark)";
+
+    thread_local! {
+        //used when localStorage is not available (private mode, disabled cookies...)
+        static CURRENT: Cell<Option<Theme>> = const { Cell::new(None) };
+    }
+
+    /// Theme of the page, System follows prefers-color-scheme.
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+    pub enum Theme {
+        Light,
+        Dark,
+        #[default]
+        System,
+    }
+
+    impl Theme {
+        /// Get the value used in data-theme and localStorage.
+        pub fn as_str(&self) -> &'static str {
+            match self {
+                Theme::Light => "light",
+                Theme::Dark => "dark",
+                Theme::System => "system",
+            }
+        }
+        /// Get a Theme from "light", "dark" or "system".
+        pub fn parse(value: &str) -> Option<Theme> {
+            match value.trim() {
+                "light" => Some(Theme::Light),
+                "dark" => Some(Theme::Dark),
+                "system" => Some(Theme::System),
+                _ => None,
+            }
+        }
+        /// Convert System to Light or Dark with the preference of the user.
+        pub fn resolve(&self) -> Theme {
+            match self {
+                Theme::System => system_theme(),
+                theme => *theme,
+            }
+        }
+    }
+
+    /// Get the theme of the operating system, Light or Dark.
+    pub fn system_theme() -> Theme {
+        match try_media_query(DARK_QUERY) {
+            Some(query) if query.matches() => Theme::Dark,
+            _ => Theme::Light,
+        }
+    }
+
+    /// Get the theme chosen by the user, System if there's not a saved theme.
+    pub fn current_theme() -> Theme {
+        let saved = crate::window()
+            .local_storage()
+            .ok()
+            .flatten()
+            .and_then(|storage| storage.get_item(THEME_KEY).ok().flatten())
+            .and_then(|value| Theme::parse(&value));
+        saved.or_else(|| CURRENT.with(|c| c.get())).unwrap_or_default()
+    }
+
+    /// Apply a theme on document_element: data-theme, class theme-light/theme-dark and color-scheme.
+    pub fn apply_theme(theme: Theme) {
+        let (resolved, other) = match theme.resolve() {
+            Theme::Dark => (Theme::Dark, Theme::Light),
+            _ => (Theme::Light, Theme::Dark),
+        };
+        let root = crate::window().document_element_html();
+        root.set_attr("data-theme", resolved.as_str());
+        root.remove_class(&format!("theme-{}", other.as_str()));
+        root.add_class(&format!("theme-{}", resolved.as_str()));
+        root.set_prop("color-scheme", resolved.as_str());
+    }
+
+    /// Save and apply a theme, every use_theme() is notified.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use minimal_web::theme::{set_theme, Theme};
+    ///
+    /// set_theme(Theme::Dark);
+    /// ```
+    pub fn set_theme(theme: Theme) {
+        CURRENT.with(|c| c.set(Some(theme)));
+        if let Some(storage) = crate::window().local_storage().ok().flatten() {
+            let _ = storage.set_item(THEME_KEY, theme.as_str());
+        }
+        apply_theme(theme);
+        if let Ok(event) = Event::new(THEME_EVENT) {
+            let _ = crate::window().dispatch_event(&event);
+        }
+    }
+
+    /// Apply the saved theme and follow prefers-color-scheme while the theme is System.
+    /// Call it once at start, without Yew use use_theme() instead.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// fn main() {
+    ///     minimal_web::theme::init_theme().forget();
+    /// }
+    /// ```
+    pub fn init_theme() -> ThemeWatcher {
+        apply_theme(current_theme());
+        ThemeWatcher::new(|_| apply_theme(current_theme()))
+    }
+
+    /// Call a function when the system theme or the chosen theme changes, stops when dropped.
+    pub struct ThemeWatcher {
+        system: Option<Listener>,
+        chosen: Listener,
+    }
+
+    impl ThemeWatcher {
+        /// The callback receives the resolved theme, Light or Dark.
+        pub fn new<F>(callback: F) -> ThemeWatcher
+        where
+            F: Fn(Theme) + 'static,
+        {
+            let callback = std::rc::Rc::new(callback);
+            let on_system = callback.clone();
+            let system = try_media_query(DARK_QUERY).map(|query| query.on_change(move |_| on_system(current_theme().resolve())));
+            let chosen = listen(&crate::window(), THEME_EVENT, move |_e| callback(current_theme().resolve()));
+            ThemeWatcher {
+                system,
+                chosen,
+            }
+        }
+
+        /// Keep watching forever.
+        pub fn forget(self) {
+            if let Some(system) = self.system {
+                system.forget();
+            }
+            self.chosen.forget();
+        }
+    }
+
+    /// Handle returned by use_theme.
+    #[derive(Clone, PartialEq)]
+    pub struct UseThemeHandle {
+        theme: Theme,
+        resolved: Theme,
+    }
+
+    impl UseThemeHandle {
+        /// Theme chosen by the user, can be System.
+        pub fn theme(&self) -> Theme {
+            self.theme
+        }
+        /// Theme shown on the page, Light or Dark.
+        pub fn resolved(&self) -> Theme {
+            self.resolved
+        }
+        /// Change the theme, same as set_theme().
+        pub fn set(&self, theme: Theme) {
+            set_theme(theme);
+        }
+        /// Switch between Light and Dark.
+        pub fn toggle(&self) {
+            match self.resolved {
+                Theme::Dark => set_theme(Theme::Light),
+                _ => set_theme(Theme::Dark),
+            }
+        }
+    }
+
+    /// Get the current theme and a setter, the page is updated when the theme changes.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use minimal_web::theme::{use_theme, Theme};
+    ///
+    /// #[function_component]
+    /// fn ThemeButton() -> Html {
+    ///     let theme = use_theme();
+    ///     let onclick = { let theme = theme.clone(); Callback::from(move |_| theme.toggle()) };
+    ///     html!{ <button {onclick}>{ theme.resolved().as_str() }</button> }
+    /// }
+    /// ```
+    #[hook]
+    pub fn use_theme() -> UseThemeHandle {
+        let state = use_state(|| (current_theme(), current_theme().resolve()));
+        {
+            let state = state.clone();
+            use_effect_with_deps(
+                move |_| {
+                    apply_theme(current_theme());
+                    let watcher = ThemeWatcher::new(move |resolved| {
+                        apply_theme(current_theme());
+                        state.set((current_theme(), resolved));
+                    });
+                    move || drop(watcher)
+                },
+                (),
+            );
+        }
+        UseThemeHandle {
+            theme: state.0,
+            resolved: state.1,
+        }
+    }
+}
+pub mod media {
+    use std::cell::Cell;
+    use web_sys::MediaQueryList;
+    use yew::prelude::*;
+    use crate::utils::*;
+
+    thread_local! {
+        static BREAKPOINTS: Cell<Breakpoints> = const { Cell::new(Breakpoints::DEFAULT) };
+    }
+
+    /// A live media query, matches() is always updated.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use minimal_web::media::media_query;
+    ///
+    /// let mobile = media_query("(max-width: 768px)");
+    /// if mobile.matches() {
+    ///     //do something
+    /// }
+    /// let listener = mobile.on_change(|matches| gloo::console::log!(matches));
+    /// ```
+    #[derive(Clone, Debug)]
+    pub struct MediaQuery {
+        list: MediaQueryList,
+    }
+
+    /// Create a MediaQuery, same as match_media().
+    pub fn media_query(query: &str) -> MediaQuery {
+        let list = crate::window()
+            .match_media(query)
+            .custom_expect("It's not possible read this media query : ".to_owned() + query)
+            .custom_expect("There's not a MediaQueryList for : ".to_owned() + query);
+        MediaQuery { list }
+    }
+
+    /// Same as media_query(), None when matchMedia is not available instead of panicking.
+    pub fn try_media_query(query: &str) -> Option<MediaQuery> {
+        let list = crate::window().match_media(query).ok().flatten()?;
+        Some(MediaQuery { list })
+    }
+
+    impl MediaQuery {
+        /// Check if the media query matches now.
+        pub fn matches(&self) -> bool {
+            self.list.matches()
+        }
+        /// Get the media query text, same as media().
+        pub fn media(&self) -> String {
+            self.list.media()
+        }
+        /// Call a function every time the media query starts or stops matching, stops when the Listener is dropped.
+        pub fn on_change<F>(&self, mut callback: F) -> Listener
+        where
+            F: FnMut(bool) + 'static,
+        {
+            let list = self.list.clone();
+            listen(&self.list, "change", move |_e| callback(list.matches()))
+        }
+        /// Get the MediaQueryList.
+        pub fn list(&self) -> &MediaQueryList {
+            &self.list
+        }
+    }
+
+    /// Breakpoints of the page, from the smallest to the largest.
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+    pub enum Breakpoint {
+        Xs,
+        Sm,
+        Md,
+        Lg,
+        Xl,
+    }
+
+    /// Min width in px of every breakpoint, Xs is always 0.
+    #[derive(Clone, Copy, Debug, PartialEq)]
+    pub struct Breakpoints {
+        pub sm: f64,
+        pub md: f64,
+        pub lg: f64,
+        pub xl: f64,
+    }
+
+    impl Default for Breakpoints {
+        fn default() -> Self {
+            Breakpoints::DEFAULT
+        }
+    }
+
+    impl Breakpoints {
+        /// The breakpoints of Tailwind, same as Breakpoints::default().
+        pub const DEFAULT: Breakpoints = Breakpoints {
+            sm: 640.0,
+            md: 768.0,
+            lg: 1024.0,
+            xl: 1280.0,
+        };
+
+        /// Get the min width of a breakpoint.
+        pub fn min_width(&self, breakpoint: Breakpoint) -> f64 {
+            match breakpoint {
+                Breakpoint::Xs => 0.0,
+                Breakpoint::Sm => self.sm,
+                Breakpoint::Md => self.md,
+                Breakpoint::Lg => self.lg,
+                Breakpoint::Xl => self.xl,
+            }
+        }
+        /// Get the media query of a breakpoint, for example "(min-width: 768px)".
+        pub fn query(&self, breakpoint: Breakpoint) -> String {
+            format!("(min-width: {}px)", self.min_width(breakpoint))
+        }
+        /// Get the breakpoint of a width.
+        ///
+        /// # Examples
+        ///
+        /// ```ignore
+        /// let breakpoints = Breakpoints::default();
+        /// assert_eq!(Breakpoint::Md, breakpoints.breakpoint_for(800.0));
+        /// ```
+        pub fn breakpoint_for(&self, width: f64) -> Breakpoint {
+            Breakpoint::ALL
+                .iter()
+                .rev()
+                .copied()
+                .find(|b| width >= self.min_width(*b))
+                .unwrap_or(Breakpoint::Xs)
+        }
+    }
+
+    impl Breakpoint {
+        /// All breakpoints from the smallest.
+        pub const ALL: [Breakpoint; 5] = [Breakpoint::Xs, Breakpoint::Sm, Breakpoint::Md, Breakpoint::Lg, Breakpoint::Xl];
+    }
+
+    /// Change the breakpoints used by current_breakpoint() and use_breakpoint(), call it at start.
+    pub fn set_breakpoints(breakpoints: Breakpoints) {
+        BREAKPOINTS.with(|b| b.set(breakpoints));
+    }
+
+    /// Get the breakpoints in use.
+    pub fn breakpoints() -> Breakpoints {
+        BREAKPOINTS.with(|b| b.get())
+    }
+
+    /// Get the breakpoint of the page now.
+    pub fn current_breakpoint() -> Breakpoint {
+        let breakpoints = breakpoints();
+        Breakpoint::ALL
+            .iter()
+            .rev()
+            .copied()
+            .find(|b| *b == Breakpoint::Xs || media_query(&breakpoints.query(*b)).matches())
+            .unwrap_or(Breakpoint::Xs)
+    }
+
+    /// Call a function when the breakpoint changes, stops when dropped.
+    pub struct BreakpointWatcher {
+        listeners: Vec<Listener>,
+    }
+
+    impl BreakpointWatcher {
+        /// The callback receives the new breakpoint.
+        pub fn new<F>(callback: F) -> BreakpointWatcher
+        where
+            F: Fn(Breakpoint) + 'static,
+        {
+            let callback = std::rc::Rc::new(callback);
+            let breakpoints = breakpoints();
+            let listeners = Breakpoint::ALL[1..]
+                .iter()
+                .map(|b| {
+                    let callback = callback.clone();
+                    media_query(&breakpoints.query(*b)).on_change(move |_| callback(current_breakpoint()))
+                })
+                .collect();
+            BreakpointWatcher { listeners }
+        }
+
+        /// Keep watching forever.
+        pub fn forget(self) {
+            for listener in self.listeners {
+                listener.forget();
+            }
+        }
+    }
+
+    /// Check if a media query matches, the component is updated when it changes.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let mobile = use_media_query("(max-width: 768px)");
+    /// html!{ if mobile { <MobileMenu /> } else { <Menu /> } }
+    /// ```
+    #[hook]
+    pub fn use_media_query(query: &str) -> bool {
+        let query = query.to_owned();
+        let matches = {
+            let query = query.clone();
+            use_state(move || media_query(&query).matches())
+        };
+        {
+            let matches = matches.clone();
+            use_effect_with_deps(
+                move |query| {
+                    let media = media_query(query);
+                    matches.set(media.matches());
+                    let listener = media.on_change(move |m| matches.set(m));
+                    move || drop(listener)
+                },
+                query,
+            );
+        }
+        *matches
+    }
+
+    /// Get the breakpoint of the page, the component is updated when it changes.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let breakpoint = use_breakpoint();
+    /// let columns = if breakpoint >= Breakpoint::Lg { 3 } else { 1 };
+    /// ```
+    #[hook]
+    pub fn use_breakpoint() -> Breakpoint {
+        let breakpoint = use_state(current_breakpoint);
+        {
+            let breakpoint = breakpoint.clone();
+            use_effect_with_deps(
+                move |_| {
+                    let watcher = BreakpointWatcher::new(move |b| breakpoint.set(b));
+                    move || drop(watcher)
+                },
+                (),
+            );
+        }
+        *breakpoint
+    }
+}
+pub mod animation{
+    use std::cell::RefCell;
+    use std::future::Future;
+    use std::pin::Pin;
+    use std::rc::{Rc, Weak};
+    use std::task::{Context, Poll, Waker};
+    use wasm_bindgen::prelude::*;
+    use yew::prelude::*;
+    use web_sys::{Element, HtmlElement, NodeList};
+    use crate::utils::*;
+    pub use easing::{Easing, Lerp, StepPosition};
+    pub use spring::{Spring, SpringState, SpringValue};
+    pub use keyframes::{deg, percent, px, AnimationHandle, Keyframe, Timing};
+    pub use web_sys::{FillMode, PlaybackDirection};
+
+    pub mod easing {
+        //! Easing functions and interpolation, pure Rust so they work outside the browser too.
+        use std::f64::consts::PI;
+
+        /// Position of the jump for Easing::Steps, same as jump-start and jump-end of css.
+        #[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+        pub enum StepPosition {
+            Start,
+            #[default]
+            End,
+        }
+
+        /// Easing of an animation, see <https://easings.net> for the curves.
+        ///
+        /// # Examples
+        ///
+        /// ```ignore
+        /// assert_eq!(0.5, Easing::Linear.apply(0.5));
+        /// assert_eq!(1.0, Easing::OutBounce.apply(1.0));
+        /// let ease = Easing::CubicBezier(0.25, 0.1, 0.25, 1.0);
+        /// ```
+        #[derive(Clone, Copy, Debug, PartialEq, Default)]
+        pub enum Easing {
+            #[default]
+            Linear,
+            InQuad,
+            OutQuad,
+            InOutQuad,
+            InCubic,
+            OutCubic,
+            InOutCubic,
+            InQuart,
+            OutQuart,
+            InOutQuart,
+            InSine,
+            OutSine,
+            InOutSine,
+            InExpo,
+            OutExpo,
+            InOutExpo,
+            InCirc,
+            OutCirc,
+            InOutCirc,
+            InBack,
+            OutBack,
+            InOutBack,
+            InElastic,
+            OutElastic,
+            InOutElastic,
+            InBounce,
+            OutBounce,
+            InOutBounce,
+            /// Same as cubic-bezier(x1, y1, x2, y2) of css.
+            CubicBezier(f64, f64, f64, f64),
+            /// Same as steps(n, jump-start | jump-end) of css.
+            Steps(u32, StepPosition),
+        }
+
+        impl Easing {
+            /// Same as ease of css.
+            pub const EASE: Easing = Easing::CubicBezier(0.25, 0.1, 0.25, 1.0);
+            /// Same as ease-in of css.
+            pub const EASE_IN: Easing = Easing::CubicBezier(0.42, 0.0, 1.0, 1.0);
+            /// Same as ease-out of css.
+            pub const EASE_OUT: Easing = Easing::CubicBezier(0.0, 0.0, 0.58, 1.0);
+            /// Same as ease-in-out of css.
+            pub const EASE_IN_OUT: Easing = Easing::CubicBezier(0.42, 0.0, 0.58, 1.0);
+
+            /// Get the eased value of t, t is clamped between 0 and 1.
+            pub fn apply(&self, t: f64) -> f64 {
+                let t = if t.is_nan() { 0.0 } else { t.clamp(0.0, 1.0) };
+                match *self {
+                    Easing::Linear => t,
+                    Easing::InQuad => t * t,
+                    Easing::OutQuad => 1.0 - (1.0 - t).powi(2),
+                    Easing::InOutQuad => {
+                        if t < 0.5 { 2.0 * t * t } else { 1.0 - (-2.0 * t + 2.0).powi(2) / 2.0 }
+                    }
+                    Easing::InCubic => t.powi(3),
+                    Easing::OutCubic => 1.0 - (1.0 - t).powi(3),
+                    Easing::InOutCubic => {
+                        if t < 0.5 { 4.0 * t.powi(3) } else { 1.0 - (-2.0 * t + 2.0).powi(3) / 2.0 }
+                    }
+                    Easing::InQuart => t.powi(4),
+                    Easing::OutQuart => 1.0 - (1.0 - t).powi(4),
+                    Easing::InOutQuart => {
+                        if t < 0.5 { 8.0 * t.powi(4) } else { 1.0 - (-2.0 * t + 2.0).powi(4) / 2.0 }
+                    }
+                    Easing::InSine => 1.0 - (t * PI / 2.0).cos(),
+                    Easing::OutSine => (t * PI / 2.0).sin(),
+                    Easing::InOutSine => -((PI * t).cos() - 1.0) / 2.0,
+                    Easing::InExpo => {
+                        if t == 0.0 { 0.0 } else { 2f64.powf(10.0 * t - 10.0) }
+                    }
+                    Easing::OutExpo => {
+                        if t == 1.0 { 1.0 } else { 1.0 - 2f64.powf(-10.0 * t) }
+                    }
+                    Easing::InOutExpo => {
+                        if t == 0.0 || t == 1.0 {
+                            t
+                        } else if t < 0.5 {
+                            2f64.powf(20.0 * t - 10.0) / 2.0
+                        } else {
+                            (2.0 - 2f64.powf(-20.0 * t + 10.0)) / 2.0
+                        }
+                    }
+                    Easing::InCirc => 1.0 - (1.0 - t * t).sqrt(),
+                    Easing::OutCirc => (1.0 - (t - 1.0).powi(2)).sqrt(),
+                    Easing::InOutCirc => {
+                        if t < 0.5 {
+                            (1.0 - (1.0 - (2.0 * t).powi(2)).sqrt()) / 2.0
+                        } else {
+                            ((1.0 - (-2.0 * t + 2.0).powi(2)).sqrt() + 1.0) / 2.0
+                        }
+                    }
+                    Easing::InBack => BACK_C3 * t.powi(3) - BACK_C1 * t * t,
+                    Easing::OutBack => 1.0 + BACK_C3 * (t - 1.0).powi(3) + BACK_C1 * (t - 1.0).powi(2),
+                    Easing::InOutBack => {
+                        let c2 = BACK_C1 * 1.525;
+                        if t < 0.5 {
+                            ((2.0 * t).powi(2) * ((c2 + 1.0) * 2.0 * t - c2)) / 2.0
+                        } else {
+                            ((2.0 * t - 2.0).powi(2) * ((c2 + 1.0) * (t * 2.0 - 2.0) + c2) + 2.0) / 2.0
+                        }
+                    }
+                    Easing::InElastic => {
+                        if t == 0.0 || t == 1.0 {
+                            t
+                        } else {
+                            -(2f64.powf(10.0 * t - 10.0)) * ((t * 10.0 - 10.75) * (2.0 * PI / 3.0)).sin()
+                        }
+                    }
+                    Easing::OutElastic => {
+                        if t == 0.0 || t == 1.0 {
+                            t
+                        } else {
+                            2f64.powf(-10.0 * t) * ((t * 10.0 - 0.75) * (2.0 * PI / 3.0)).sin() + 1.0
+                        }
+                    }
+                    Easing::InOutElastic => {
+                        let c5 = 2.0 * PI / 4.5;
+                        if t == 0.0 || t == 1.0 {
+                            t
+                        } else if t < 0.5 {
+                            -(2f64.powf(20.0 * t - 10.0) * ((20.0 * t - 11.125) * c5).sin()) / 2.0
+                        } else {
+                            (2f64.powf(-20.0 * t + 10.0) * ((20.0 * t - 11.125) * c5).sin()) / 2.0 + 1.0
+                        }
+                    }
+                    Easing::InBounce => 1.0 - out_bounce(1.0 - t),
+                    Easing::OutBounce => out_bounce(t),
+                    Easing::InOutBounce => {
+                        if t < 0.5 {
+                            (1.0 - out_bounce(1.0 - 2.0 * t)) / 2.0
+                        } else {
+                            (1.0 + out_bounce(2.0 * t - 1.0)) / 2.0
+                        }
+                    }
+                    Easing::CubicBezier(x1, y1, x2, y2) => cubic_bezier(x1, y1, x2, y2, t),
+                    Easing::Steps(steps, position) => {
+                        let steps = steps.max(1) as f64;
+                        //jump-start is already on the first step at 0
+                        let step = match position {
+                            StepPosition::Start => (t * steps).floor() + 1.0,
+                            StepPosition::End => (t * steps).floor(),
+                        };
+                        (step / steps).clamp(0.0, 1.0)
+                    }
+                }
+            }
+        }
+
+        impl Easing {
+            /// Get the easing as css, usable in transition-timing-function and in the Web Animations API.
+            /// Elastic and bounce have no cubic-bezier, so they are sampled in a linear() function.
+            ///
+            /// # Examples
+            ///
+            /// ```ignore
+            /// assert_eq!("linear", Easing::Linear.to_css());
+            /// assert_eq!("cubic-bezier(0.25, 0.1, 0.25, 1)", Easing::EASE.to_css());
+            /// ```
+            pub fn to_css(&self) -> String {
+                let bezier = |x1: f64, y1: f64, x2: f64, y2: f64| format!("cubic-bezier({x1}, {y1}, {x2}, {y2})");
+                match *self {
+                    Easing::Linear => "linear".to_owned(),
+                    Easing::InQuad => bezier(0.11, 0.0, 0.5, 0.0),
+                    Easing::OutQuad => bezier(0.5, 1.0, 0.89, 1.0),
+                    Easing::InOutQuad => bezier(0.45, 0.0, 0.55, 1.0),
+                    Easing::InCubic => bezier(0.32, 0.0, 0.67, 0.0),
+                    Easing::OutCubic => bezier(0.33, 1.0, 0.68, 1.0),
+                    Easing::InOutCubic => bezier(0.65, 0.0, 0.35, 1.0),
+                    Easing::InQuart => bezier(0.5, 0.0, 0.75, 0.0),
+                    Easing::OutQuart => bezier(0.25, 1.0, 0.5, 1.0),
+                    Easing::InOutQuart => bezier(0.76, 0.0, 0.24, 1.0),
+                    Easing::InSine => bezier(0.12, 0.0, 0.39, 0.0),
+                    Easing::OutSine => bezier(0.61, 1.0, 0.88, 1.0),
+                    Easing::InOutSine => bezier(0.37, 0.0, 0.63, 1.0),
+                    Easing::InExpo => bezier(0.7, 0.0, 0.84, 0.0),
+                    Easing::OutExpo => bezier(0.16, 1.0, 0.3, 1.0),
+                    Easing::InOutExpo => bezier(0.87, 0.0, 0.13, 1.0),
+                    Easing::InCirc => bezier(0.55, 0.0, 1.0, 0.45),
+                    Easing::OutCirc => bezier(0.0, 0.55, 0.45, 1.0),
+                    Easing::InOutCirc => bezier(0.85, 0.0, 0.15, 1.0),
+                    Easing::InBack => bezier(0.36, 0.0, 0.66, -0.56),
+                    Easing::OutBack => bezier(0.34, 1.56, 0.64, 1.0),
+                    Easing::InOutBack => bezier(0.68, -0.6, 0.32, 1.6),
+                    Easing::CubicBezier(x1, y1, x2, y2) => bezier(x1, y1, x2, y2),
+                    Easing::Steps(steps, position) => {
+                        let position = match position {
+                            StepPosition::Start => "jump-start",
+                            StepPosition::End => "jump-end",
+                        };
+                        format!("steps({}, {position})", steps.max(1))
+                    }
+                    _ => {
+                        let points: Vec<String> = (0..=40)
+                            .map(|i| format!("{:.4}", self.apply(i as f64 / 40.0)))
+                            .collect();
+                        format!("linear({})", points.join(", "))
+                    }
+                }
+            }
+
+            /// Same as to_css without linear(), elastic and bounce get the closest cubic-bezier.
+            /// Used for browsers that don't support linear().
+            pub fn compat_css(&self) -> String {
+                match *self {
+                    Easing::InElastic | Easing::InBounce => Easing::EASE_IN.to_css(),
+                    Easing::OutElastic => Easing::OutBack.to_css(),
+                    Easing::OutBounce => Easing::EASE_OUT.to_css(),
+                    Easing::InOutElastic => Easing::InOutBack.to_css(),
+                    Easing::InOutBounce => Easing::EASE_IN_OUT.to_css(),
+                    _ => self.to_css(),
+                }
+            }
+        }
+
+        const BACK_C1: f64 = 1.70158;
+        const BACK_C3: f64 = BACK_C1 + 1.0;
+
+        fn out_bounce(t: f64) -> f64 {
+            let n1 = 7.5625;
+            let d1 = 2.75;
+            if t < 1.0 / d1 {
+                n1 * t * t
+            } else if t < 2.0 / d1 {
+                let t = t - 1.5 / d1;
+                n1 * t * t + 0.75
+            } else if t < 2.5 / d1 {
+                let t = t - 2.25 / d1;
+                n1 * t * t + 0.9375
+            } else {
+                let t = t - 2.625 / d1;
+                n1 * t * t + 0.984375
+            }
+        }
+
+        /// Get the y of a cubic-bezier(x1, y1, x2, y2) curve at x = t, same as css.
+        pub fn cubic_bezier(x1: f64, y1: f64, x2: f64, y2: f64, t: f64) -> f64 {
+            if t <= 0.0 || t >= 1.0 {
+                return t.clamp(0.0, 1.0);
+            }
+            let x1 = x1.clamp(0.0, 1.0);
+            let x2 = x2.clamp(0.0, 1.0);
+            let curve = |a: f64, b: f64, s: f64| {
+                3.0 * a * s * (1.0 - s).powi(2) + 3.0 * b * s * s * (1.0 - s) + s.powi(3)
+            };
+            let slope = |a: f64, b: f64, s: f64| {
+                3.0 * a * (1.0 - s).powi(2) + 6.0 * (b - a) * s * (1.0 - s) + 3.0 * (1.0 - b) * s * s
+            };
+            //Newton first, it's fast on most curves
+            let mut s = t;
+            for _ in 0..8 {
+                let x = curve(x1, x2, s) - t;
+                if x.abs() < 1e-7 {
+                    return curve(y1, y2, s);
+                }
+                let d = slope(x1, x2, s);
+                if d.abs() < 1e-6 {
+                    break;
+                }
+                s -= x / d;
+            }
+            //then bisection, always converges
+            let (mut low, mut high) = (0.0, 1.0);
+            s = t;
+            for _ in 0..64 {
+                let x = curve(x1, x2, s);
+                if (x - t).abs() < 1e-7 {
+                    break;
+                }
+                if x < t {
+                    low = s;
+                } else {
+                    high = s;
+                }
+                s = (low + high) / 2.0;
+            }
+            curve(y1, y2, s)
+        }
+
+        /// Get the value between from and to at t, t = 0 is from and t = 1 is to.
+        pub fn lerp(from: f64, to: f64, t: f64) -> f64 {
+            from + (to - from) * t
+        }
+
+        /// A value that can be animated.
+        pub trait Lerp: Clone {
+            /// Get the value between self and to at t.
+            fn lerp(&self, to: &Self, t: f64) -> Self;
+        }
+
+        impl Lerp for f64 {
+            fn lerp(&self, to: &Self, t: f64) -> Self {
+                lerp(*self, *to, t)
+            }
+        }
+
+        impl Lerp for f32 {
+            fn lerp(&self, to: &Self, t: f64) -> Self {
+                lerp(*self as f64, *to as f64, t) as f32
+            }
+        }
+
+        impl Lerp for (f64, f64) {
+            fn lerp(&self, to: &Self, t: f64) -> Self {
+                (lerp(self.0, to.0, t), lerp(self.1, to.1, t))
+            }
+        }
+
+        impl Lerp for (f64, f64, f64) {
+            fn lerp(&self, to: &Self, t: f64) -> Self {
+                (lerp(self.0, to.0, t), lerp(self.1, to.1, t), lerp(self.2, to.2, t))
+            }
+        }
+
+        impl<const N: usize> Lerp for [f64; N] {
+            fn lerp(&self, to: &Self, t: f64) -> Self {
+                let mut value = *self;
+                for (i, v) in value.iter_mut().enumerate() {
+                    *v = lerp(self[i], to[i], t);
+                }
+                value
+            }
+        }
+
+        #[cfg(test)]
+        mod tests {
+            use super::*;
+
+            const ALL: [Easing; 33] = [
+                Easing::Linear,
+                Easing::InQuad,
+                Easing::OutQuad,
+                Easing::InOutQuad,
+                Easing::InCubic,
+                Easing::OutCubic,
+                Easing::InOutCubic,
+                Easing::InQuart,
+                Easing::OutQuart,
+                Easing::InOutQuart,
+                Easing::InSine,
+                Easing::OutSine,
+                Easing::InOutSine,
+                Easing::InExpo,
+                Easing::OutExpo,
+                Easing::InOutExpo,
+                Easing::InCirc,
+                Easing::OutCirc,
+                Easing::InOutCirc,
+                Easing::InBack,
+                Easing::OutBack,
+                Easing::InOutBack,
+                Easing::InElastic,
+                Easing::OutElastic,
+                Easing::InOutElastic,
+                Easing::InBounce,
+                Easing::OutBounce,
+                Easing::InOutBounce,
+                Easing::EASE,
+                Easing::EASE_IN,
+                Easing::EASE_OUT,
+                Easing::EASE_IN_OUT,
+                Easing::Steps(4, StepPosition::End),
+            ];
+
+            #[test]
+            fn endpoints() {
+                for easing in ALL {
+                    assert!(easing.apply(0.0).abs() < 1e-9, "{easing:?} at 0");
+                    assert!((easing.apply(1.0) - 1.0).abs() < 1e-9, "{easing:?} at 1");
+                    assert_eq!(easing.apply(-1.0), easing.apply(0.0), "{easing:?} is clamped");
+                    assert_eq!(easing.apply(2.0), easing.apply(1.0), "{easing:?} is clamped");
+                }
+            }
+
+            #[test]
+            fn cubic_bezier_is_monotonic() {
+                let curves = [
+                    Easing::EASE,
+                    Easing::EASE_IN,
+                    Easing::EASE_OUT,
+                    Easing::EASE_IN_OUT,
+                    Easing::CubicBezier(0.0, 0.0, 1.0, 1.0),
+                    Easing::CubicBezier(0.9, 0.1, 0.1, 0.9),
+                    Easing::CubicBezier(0.0, 1.0, 0.0, 1.0),
+                ];
+                for easing in curves {
+                    let mut last = 0.0;
+                    for i in 0..=1000 {
+                        let y = easing.apply(i as f64 / 1000.0);
+                        assert!(y >= last - 1e-9, "{easing:?} goes back at {i}");
+                        last = y;
+                    }
+                }
+                assert!((Easing::CubicBezier(0.0, 0.0, 1.0, 1.0).apply(0.3) - 0.3).abs() < 1e-6);
+                assert!((Easing::EASE.apply(0.5) - 0.8024).abs() < 1e-3);
+            }
+
+            #[test]
+            fn steps() {
+                let end = Easing::Steps(4, StepPosition::End);
+                assert_eq!(end.apply(0.0), 0.0);
+                assert_eq!(end.apply(0.24), 0.0);
+                assert_eq!(end.apply(0.25), 0.25);
+                assert_eq!(end.apply(0.99), 0.75);
+                assert_eq!(end.apply(1.0), 1.0);
+
+                let start = Easing::Steps(4, StepPosition::Start);
+                assert_eq!(start.apply(0.0), 0.25);
+                assert_eq!(start.apply(0.24), 0.25);
+                assert_eq!(start.apply(0.25), 0.5);
+                assert_eq!(start.apply(0.99), 1.0);
+                assert_eq!(start.apply(1.0), 1.0);
+
+                assert_eq!(Easing::Steps(0, StepPosition::End).apply(0.5), 0.0);
+            }
+
+            #[test]
+            fn compat_css_has_no_linear_function() {
+                for easing in ALL {
+                    assert!(!easing.compat_css().contains("linear("), "{easing:?}");
+                }
+            }
+
+            #[test]
+            fn lerp_values() {
+                assert_eq!(lerp(10.0, 20.0, 0.0), 10.0);
+                assert_eq!(lerp(10.0, 20.0, 0.5), 15.0);
+                assert_eq!(lerp(10.0, 20.0, 1.0), 20.0);
+                assert_eq!(2.0f32.lerp(&4.0, 0.5), 3.0);
+            }
+        }
+    }
+
+    pub mod spring {
+        //! Spring physics, pure Rust so they work outside the browser too.
+
+        /// A damped spring, times are in milliseconds.
+        ///
+        /// # Examples
+        ///
+        /// ```ignore
+        /// let spring = Spring { stiffness: 200.0, damping: 20.0, ..Default::default() };
+        /// let mut state = SpringState::new(&0.0_f64);
+        /// state.set_target(&100.0);
+        /// while !state.step(&spring, 16.0) {}
+        /// assert_eq!(100.0, state.value::<f64>());
+        /// ```
+        #[derive(Clone, Copy, Debug, PartialEq)]
+        pub struct Spring {
+            pub stiffness: f64,
+            pub damping: f64,
+            pub mass: f64,
+            /// Velocity under which the spring can rest, in units per second.
+            pub rest_speed: f64,
+            /// Distance from target under which the spring can rest.
+            pub rest_delta: f64,
+        }
+
+        impl Default for Spring {
+            fn default() -> Self {
+                Spring {
+                    stiffness: 170.0,
+                    damping: 26.0,
+                    mass: 1.0,
+                    rest_speed: 0.01,
+                    rest_delta: 0.01,
+                }
+            }
+        }
+
+        impl Spring {
+            pub const GENTLE: Spring = Spring { stiffness: 120.0, damping: 14.0, mass: 1.0, rest_speed: 0.01, rest_delta: 0.01 };
+            pub const WOBBLY: Spring = Spring { stiffness: 180.0, damping: 12.0, mass: 1.0, rest_speed: 0.01, rest_delta: 0.01 };
+            pub const STIFF: Spring = Spring { stiffness: 210.0, damping: 20.0, mass: 1.0, rest_speed: 0.01, rest_delta: 0.01 };
+            pub const SLOW: Spring = Spring { stiffness: 280.0, damping: 60.0, mass: 1.0, rest_speed: 0.01, rest_delta: 0.01 };
+
+            /// Move a value toward target for dt milliseconds, return the new value and velocity.
+            pub fn step(&self, value: f64, velocity: f64, target: f64, dt: f64) -> (f64, f64) {
+                let mass = if self.mass > 0.0 { self.mass } else { 1.0 };
+                let (mut value, mut velocity) = (value, velocity);
+                //fixed steps of 1ms keep stiff springs stable, long frames are capped at 64ms
+                let mut remaining = dt.clamp(0.0, 64.0);
+                while remaining > 0.0 {
+                    let h = remaining.min(1.0) / 1000.0;
+                    let force = -self.stiffness * (value - target) - self.damping * velocity;
+                    velocity += force / mass * h;
+                    value += velocity * h;
+                    remaining -= 1.0;
+                }
+                (value, velocity)
+            }
+
+            /// Check if a value has stopped on target.
+            pub fn is_at_rest(&self, value: f64, velocity: f64, target: f64) -> bool {
+                velocity.abs() < self.rest_speed && (value - target).abs() < self.rest_delta
+            }
+        }
+
+        /// A value that can be moved by a Spring, every component has its own velocity.
+        pub trait SpringValue: Clone {
+            fn components(&self) -> Vec<f64>;
+            fn from_components(components: &[f64]) -> Self;
+        }
+
+        impl SpringValue for f64 {
+            fn components(&self) -> Vec<f64> {
+                vec![*self]
+            }
+            fn from_components(components: &[f64]) -> Self {
+                components.first().copied().unwrap_or_default()
+            }
+        }
+
+        impl SpringValue for f32 {
+            fn components(&self) -> Vec<f64> {
+                vec![*self as f64]
+            }
+            fn from_components(components: &[f64]) -> Self {
+                components.first().copied().unwrap_or_default() as f32
+            }
+        }
+
+        impl SpringValue for (f64, f64) {
+            fn components(&self) -> Vec<f64> {
+                vec![self.0, self.1]
+            }
+            fn from_components(components: &[f64]) -> Self {
+                let get = |i: usize| components.get(i).copied().unwrap_or_default();
+                (get(0), get(1))
+            }
+        }
+
+        impl SpringValue for (f64, f64, f64) {
+            fn components(&self) -> Vec<f64> {
+                vec![self.0, self.1, self.2]
+            }
+            fn from_components(components: &[f64]) -> Self {
+                let get = |i: usize| components.get(i).copied().unwrap_or_default();
+                (get(0), get(1), get(2))
+            }
+        }
+
+        impl<const N: usize> SpringValue for [f64; N] {
+            fn components(&self) -> Vec<f64> {
+                self.to_vec()
+            }
+            fn from_components(components: &[f64]) -> Self {
+                let mut value = [0.0; N];
+                for (v, c) in value.iter_mut().zip(components) {
+                    *v = *c;
+                }
+                value
+            }
+        }
+
+        /// Value, velocity and target of a spring, velocity is kept when the target changes.
+        #[derive(Clone, Debug, PartialEq)]
+        pub struct SpringState {
+            pub value: Vec<f64>,
+            pub velocity: Vec<f64>,
+            pub target: Vec<f64>,
+        }
+
+        impl SpringState {
+            /// Create a state at rest on value.
+            pub fn new<T: SpringValue>(value: &T) -> SpringState {
+                let value = value.components();
+                SpringState {
+                    velocity: vec![0.0; value.len()],
+                    target: value.clone(),
+                    value,
+                }
+            }
+            /// Change the target, the velocity is not changed.
+            pub fn set_target<T: SpringValue>(&mut self, target: &T) {
+                self.target = target.components();
+                self.velocity.resize(self.target.len(), 0.0);
+                self.value.resize(self.target.len(), 0.0);
+            }
+            /// Jump to a value and stop there.
+            pub fn set_value<T: SpringValue>(&mut self, value: &T) {
+                self.value = value.components();
+                self.target = self.value.clone();
+                self.velocity = vec![0.0; self.value.len()];
+            }
+            /// Get the value.
+            pub fn value<T: SpringValue>(&self) -> T {
+                T::from_components(&self.value)
+            }
+            /// Check if every component has stopped on target.
+            pub fn is_at_rest(&self, spring: &Spring) -> bool {
+                self.value
+                    .iter()
+                    .zip(&self.velocity)
+                    .zip(&self.target)
+                    .all(|((v, s), t)| spring.is_at_rest(*v, *s, *t))
+            }
+            /// Move for dt milliseconds, return true when at rest (value is snapped on target).
+            pub fn step(&mut self, spring: &Spring, dt: f64) -> bool {
+                for i in 0..self.value.len() {
+                    let (value, velocity) = spring.step(self.value[i], self.velocity[i], self.target[i], dt);
+                    self.value[i] = value;
+                    self.velocity[i] = velocity;
+                }
+                let rest = self.is_at_rest(spring);
+                if rest {
+                    self.value = self.target.clone();
+                    self.velocity.iter_mut().for_each(|v| *v = 0.0);
+                }
+                rest
+            }
+        }
+
+        #[cfg(test)]
+        mod tests {
+            use super::*;
+
+            //run frames of 16ms until rest, return the number of frames and the farthest value
+            fn settle(spring: &Spring, from: f64, to: f64) -> (usize, f64) {
+                let mut state = SpringState::new(&from);
+                state.set_target(&to);
+                let mut farthest = from;
+                for frame in 1..=1000 {
+                    let rest = state.step(spring, 16.0);
+                    let value = state.value::<f64>();
+                    if (value - from).abs() > (farthest - from).abs() {
+                        farthest = value;
+                    }
+                    if rest {
+                        return (frame, farthest);
+                    }
+                }
+                panic!("{spring:?} never comes to rest");
+            }
+
+            #[test]
+            fn converges_without_overshoot() {
+                //damping ratio close to 1, the value never goes past the target
+                for spring in [Spring::default(), Spring::SLOW] {
+                    let (frames, farthest) = settle(&spring, 0.0, 100.0);
+                    assert!(frames < 200, "{spring:?} takes {frames} frames");
+                    assert!(farthest <= 100.0 + spring.rest_delta, "{spring:?} overshoots to {farthest}");
+                }
+            }
+
+            #[test]
+            fn converges_with_overshoot() {
+                for spring in [Spring::WOBBLY, Spring::GENTLE] {
+                    let (frames, farthest) = settle(&spring, 0.0, 100.0);
+                    assert!(frames < 300, "{spring:?} takes {frames} frames");
+                    assert!(farthest > 100.0 + 1.0, "{spring:?} doesn't overshoot");
+                }
+                let (_, farthest) = settle(&Spring::WOBBLY, 100.0, 0.0);
+                assert!(farthest < -1.0);
+            }
+
+            #[test]
+            fn rest_threshold() {
+                let spring = Spring::default();
+                assert!(spring.is_at_rest(100.005, 0.005, 100.0));
+                assert!(!spring.is_at_rest(100.02, 0.0, 100.0));
+                assert!(!spring.is_at_rest(100.0, 0.02, 100.0));
+
+                let mut state = SpringState::new(&0.0);
+                state.set_target(&100.0);
+                while !state.step(&spring, 16.0) {
+                    assert!(!state.is_at_rest(&spring));
+                }
+                //snapped on target and stopped
+                assert_eq!(state.value::<f64>(), 100.0);
+                assert!(state.velocity.iter().all(|v| *v == 0.0));
+                assert!(state.step(&spring, 16.0));
+                assert_eq!(state.value::<f64>(), 100.0);
+            }
+
+            #[test]
+            fn components_move_together() {
+                let spring = Spring::STIFF;
+                let mut state = SpringState::new(&(0.0, 50.0));
+                state.set_target(&(10.0, -50.0));
+                while !state.step(&spring, 16.0) {}
+                assert_eq!(state.value::<(f64, f64)>(), (10.0, -50.0));
+            }
+        }
+    }
+
+    pub mod keyframes {
+        //! Keyframe animations with the Web Animations API, they run on the compositor when possible.
+        use std::future::Future;
+        use wasm_bindgen::prelude::*;
+        use web_sys::{AnimationPlayState, Element, FillMode, PlaybackDirection};
+        use crate::utils::ResultExt;
+        use super::Easing;
+
+        #[wasm_bindgen]
+        extern {
+            type AnimatableElement;
+
+            #[wasm_bindgen(method, catch, js_name = animate)]
+            fn animate(element: &AnimatableElement, keyframes: &js_sys::Array, options: &js_sys::Object) -> Result<web_sys::Animation, JsValue>;
+        }
+
+        /// Get a length in px, for example px(20) is "20px".
+        pub fn px<T: Into<f64>>(value: T) -> String {
+            format!("{}px", value.into())
+        }
+
+        /// Get a length in %, for example percent(50) is "50%".
+        pub fn percent<T: Into<f64>>(value: T) -> String {
+            format!("{}%", value.into())
+        }
+
+        /// Get an angle in deg, for example deg(45) is "45deg".
+        pub fn deg<T: Into<f64>>(value: T) -> String {
+            format!("{}deg", value.into())
+        }
+
+        /// A keyframe of animate_keyframes, transforms are joined in the order they are added.
+        ///
+        /// # Examples
+        ///
+        /// ```ignore
+        /// let from = Keyframe::new().opacity(0.0).translate_y(px(20));
+        /// let to = Keyframe::new().opacity(1.0).translate_y(px(0));
+        /// ```
+        #[derive(Clone, Debug, Default, PartialEq)]
+        pub struct Keyframe {
+            properties: Vec<(String, String)>,
+            transforms: Vec<String>,
+            offset: Option<f64>,
+            easing: Option<Easing>,
+        }
+
+        impl Keyframe {
+            pub fn new() -> Keyframe {
+                Keyframe::default()
+            }
+            /// Set any css property, for example set("background-color", "red").
+            pub fn set(mut self, property: &str, value: &str) -> Keyframe {
+                let property = css_to_camel(property);
+                self.properties.retain(|(p, _)| *p != property);
+                self.properties.push((property, value.to_owned()));
+                self
+            }
+            pub fn opacity(self, value: f64) -> Keyframe {
+                self.set("opacity", &value.to_string())
+            }
+            /// Add a transform function, for example transform("skewX(10deg)").
+            pub fn transform(mut self, value: &str) -> Keyframe {
+                self.transforms.push(value.to_owned());
+                self
+            }
+            pub fn translate(self, x: impl std::fmt::Display, y: impl std::fmt::Display) -> Keyframe {
+                self.transform(&format!("translate({x}, {y})"))
+            }
+            pub fn translate_x(self, value: impl std::fmt::Display) -> Keyframe {
+                self.transform(&format!("translateX({value})"))
+            }
+            pub fn translate_y(self, value: impl std::fmt::Display) -> Keyframe {
+                self.transform(&format!("translateY({value})"))
+            }
+            pub fn scale(self, value: f64) -> Keyframe {
+                self.transform(&format!("scale({value})"))
+            }
+            /// Rotate in deg.
+            pub fn rotate(self, value: f64) -> Keyframe {
+                self.transform(&format!("rotate({value}deg)"))
+            }
+            /// Position of the keyframe between 0 and 1, by default keyframes are evenly spaced.
+            pub fn offset(mut self, value: f64) -> Keyframe {
+                self.offset = Some(value.clamp(0.0, 1.0));
+                self
+            }
+            /// Easing from this keyframe to the next one.
+            pub fn easing(mut self, value: Easing) -> Keyframe {
+                self.easing = Some(value);
+                self
+            }
+            /// Convert the keyframe to the object used by the Web Animations API.
+            pub fn to_js(&self) -> js_sys::Object {
+                self.js_with(Easing::to_css)
+            }
+            fn js_with(&self, easing_css: fn(&Easing) -> String) -> js_sys::Object {
+                let object = js_sys::Object::new();
+                let set = |key: &str, value: JsValue| {
+                    let _ = js_sys::Reflect::set(&object, &key.into(), &value);
+                };
+                for (property, value) in &self.properties {
+                    set(property, value.into());
+                }
+                if !self.transforms.is_empty() {
+                    set("transform", self.transforms.join(" ").into());
+                }
+                if let Some(offset) = self.offset {
+                    set("offset", offset.into());
+                }
+                if let Some(easing) = self.easing {
+                    set("easing", easing_css(&easing).into());
+                }
+                object
+            }
+        }
+
+        //background-color -> backgroundColor, the Web Animations API wants camelCase
+        fn css_to_camel(property: &str) -> String {
+            if property.starts_with("--") {
+                return property.to_owned();
+            }
+            let mut camel = String::with_capacity(property.len());
+            let mut upper = false;
+            for c in property.chars() {
+                if c == '-' {
+                    upper = !camel.is_empty();
+                } else if upper {
+                    camel.extend(c.to_uppercase());
+                    upper = false;
+                } else {
+                    camel.push(c);
+                }
+            }
+            camel
+        }
+
+        /// Timing of animate_keyframes, times are in milliseconds.
+        ///
+        /// # Examples
+        ///
+        /// ```ignore
+        /// let timing = Timing {
+        ///     duration: 300.0,
+        ///     easing: Easing::OutCubic,
+        ///     fill: FillMode::Forwards,
+        ///     ..Default::default()
+        /// };
+        /// ```
+        #[derive(Clone, Copy, Debug, PartialEq)]
+        pub struct Timing {
+            pub duration: f64,
+            pub delay: f64,
+            pub end_delay: f64,
+            pub easing: Easing,
+            pub fill: FillMode,
+            /// f64::INFINITY for repeat forever.
+            pub iterations: f64,
+            pub direction: PlaybackDirection,
+        }
+
+        impl Default for Timing {
+            fn default() -> Self {
+                Timing {
+                    duration: 300.0,
+                    delay: 0.0,
+                    end_delay: 0.0,
+                    easing: Easing::EASE,
+                    fill: FillMode::Auto,
+                    iterations: 1.0,
+                    direction: PlaybackDirection::Normal,
+                }
+            }
+        }
+
+        impl Timing {
+            /// Convert the timing to the options used by the Web Animations API.
+            pub fn to_js(&self) -> js_sys::Object {
+                self.js_with(Easing::to_css)
+            }
+            fn js_with(&self, easing_css: fn(&Easing) -> String) -> js_sys::Object {
+                let object = js_sys::Object::new();
+                let set = |key: &str, value: JsValue| {
+                    let _ = js_sys::Reflect::set(&object, &key.into(), &value);
+                };
+                set("duration", self.duration.max(0.0).into());
+                set("delay", self.delay.into());
+                set("endDelay", self.end_delay.into());
+                set("easing", easing_css(&self.easing).into());
+                set("fill", self.fill.into());
+                set("iterations", self.iterations.max(0.0).into());
+                set("direction", self.direction.into());
+                object
+            }
+        }
+
+        /// A running keyframe animation, see MinimalHtml::animate_keyframes.
+        #[derive(Clone, Debug)]
+        pub struct AnimationHandle {
+            animation: web_sys::Animation,
+        }
+
+        /// Start a keyframe animation on element, with reduced motion it lasts 0 ms and runs once.
+        /// Browsers without linear() get a cubic-bezier close to the easing, and if the animation
+        /// still can't start the last keyframe is applied at once.
+        pub fn animate_keyframes(element: &Element, keyframes: &[Keyframe], mut timing: Timing) -> AnimationHandle {
+            if super::reduced_motion() {
+                timing.duration = 0.0;
+                timing.delay = 0.0;
+                timing.end_delay = 0.0;
+                timing.iterations = 1.0;
+            }
+            let frames = |easing_css: fn(&Easing) -> String| -> js_sys::Array {
+                keyframes.iter().map(|k| JsValue::from(k.js_with(easing_css))).collect()
+            };
+            let target = element.unchecked_ref::<AnimatableElement>();
+            let animation = target
+                .animate(&frames(Easing::to_css), &timing.to_js())
+                .or_else(|_| target.animate(&frames(Easing::compat_css), &timing.js_with(Easing::compat_css)))
+                .unwrap_or_else(|_| {
+                    if let Some(last) = keyframes.last() {
+                        apply_keyframe(element, last);
+                    }
+                    let animation = web_sys::Animation::new()
+                        .custom_expect("It's not possible create an Animation".to_owned());
+                    let _ = animation.finish();
+                    animation
+                });
+            AnimationHandle { animation }
+        }
+
+        //set the properties of a keyframe as inline styles, for the instant fallback
+        fn apply_keyframe(element: &Element, keyframe: &Keyframe) {
+            let Ok(style) = js_sys::Reflect::get(element, &"style".into()) else {
+                return;
+            };
+            let object = keyframe.to_js();
+            for key in js_sys::Object::keys(&object).iter() {
+                if key == "offset" || key == "easing" || key == "composite" {
+                    continue;
+                }
+                if let Ok(value) = js_sys::Reflect::get(&object, &key) {
+                    let _ = js_sys::Reflect::set(&style, &key, &value);
+                }
+            }
+        }
+
+        impl AnimationHandle {
+            pub fn play(&self) {
+                let _ = self.animation.play();
+            }
+            pub fn pause(&self) {
+                let _ = self.animation.pause();
+            }
+            /// Play backwards from where it is.
+            pub fn reverse(&self) {
+                let _ = self.animation.reverse();
+            }
+            /// Stop and remove the effect of the animation.
+            pub fn cancel(&self) {
+                self.animation.cancel();
+            }
+            /// Go to the end.
+            pub fn finish(&self) {
+                let _ = self.animation.finish();
+            }
+            /// Change the speed, 1.0 is normal and negative values play backwards.
+            pub fn set_speed(&self, rate: f64) {
+                self.animation.update_playback_rate(rate);
+            }
+            /// Go to a time in milliseconds.
+            pub fn seek(&self, time: f64) {
+                self.animation.set_current_time(Some(time));
+            }
+            /// Get the current time in milliseconds.
+            pub fn current_time(&self) -> f64 {
+                self.animation.current_time().unwrap_or_default()
+            }
+            pub fn play_state(&self) -> AnimationPlayState {
+                self.animation.play_state()
+            }
+            pub fn is_running(&self) -> bool {
+                self.play_state() == AnimationPlayState::Running
+            }
+            /// Get a future resolved when the animation finishes or is cancelled.
+            pub fn finished(&self) -> impl Future<Output = ()> {
+                let promise = self.animation.finished();
+                async move {
+                    if let Ok(promise) = promise {
+                        let _ = wasm_bindgen_futures::JsFuture::from(promise).await;
+                    }
+                }
+            }
+            /// Get the web_sys Animation.
+            pub fn raw(&self) -> &web_sys::Animation {
+                &self.animation
+            }
+        }
+    }
+
+    /// Event dispatched on window when the motion policy changes.
+    pub const MOTION_EVENT: &str = "minimal-motion-change";
+    const REDUCED_MOTION_QUERY: &str = "(prefers-reduced-motion: reduce)";
+
+    thread_local! {
+        static POLICY: std::cell::Cell<MotionPolicy> = const { std::cell::Cell::new(MotionPolicy::System) };
+    }
+
+    /// How the effects of this module move, System follows prefers-reduced-motion.
+    /// With reduced motion tweens, timelines, springs and keyframes jump to their end and the parallax effects stay still.
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+    pub enum MotionPolicy {
+        #[default]
+        System,
+        /// Always animate, even if the user asked for less motion.
+        Full,
+        /// Never animate.
+        Reduced,
+    }
+
+    impl MotionPolicy {
+        /// Get the value used in data-motion, "system", "full" or "reduced".
+        pub fn as_str(&self) -> &'static str {
+            match self {
+                MotionPolicy::System => "system",
+                MotionPolicy::Full => "full",
+                MotionPolicy::Reduced => "reduced",
+            }
+        }
+    }
+
+    /// Get the motion policy of the app.
+    pub fn motion_policy() -> MotionPolicy {
+        POLICY.with(|p| p.get())
+    }
+
+    /// Override the reduced motion setting of the user, every use_reduced_motion() is notified.
+    /// The policy is also written in data-motion of document_element so your css can follow it.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use minimal_web::animation::{set_motion_policy, MotionPolicy};
+    ///
+    /// set_motion_policy(MotionPolicy::Reduced);
+    /// ```
+    pub fn set_motion_policy(policy: MotionPolicy) {
+        POLICY.with(|p| p.set(policy));
+        let root = crate::window().document_element_html();
+        match policy {
+            MotionPolicy::System => root.remove_attr("data-motion"),
+            _ => root.set_attr("data-motion", policy.as_str()),
+        }
+        if let Ok(event) = web_sys::Event::new(MOTION_EVENT) {
+            let _ = crate::window().dispatch_event(&event);
+        }
+    }
+
+    /// Check if the effects must not move now, it reads prefers-reduced-motion every time.
+    pub fn reduced_motion() -> bool {
+        match motion_policy() {
+            MotionPolicy::System => crate::media::media_query(REDUCED_MOTION_QUERY).matches(),
+            MotionPolicy::Full => false,
+            MotionPolicy::Reduced => true,
+        }
+    }
+
+    /// Check if the effects must not move, the component is updated when the setting of the user or the policy changes.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let reduced = use_reduced_motion();
+    /// let class = if reduced { "card" } else { "card animated" };
+    /// ```
+    #[hook]
+    pub fn use_reduced_motion() -> bool {
+        let reduced = use_state(reduced_motion);
+        {
+            let reduced = reduced.clone();
+            use_effect_with_deps(
+                move |_| {
+                    reduced.set(reduced_motion());
+                    let on_system = reduced.clone();
+                    let system = crate::media::media_query(REDUCED_MOTION_QUERY)
+                        .on_change(move |_| on_system.set(reduced_motion()));
+                    let policy = listen(&crate::window(), MOTION_EVENT, move |_e| reduced.set(reduced_motion()));
+                    move || drop((system, policy))
+                },
+                (),
+            );
+        }
+        *reduced
+    }
+
+    /// Call a function on the next frame, same as request_animation_frame().
+    pub fn request_frame<F>(callback: F)
+    where
+        F: FnOnce(f64) + 'static,
+    {
+        let closure = Closure::once_into_js(callback);
+        crate::window()
+            .request_animation_frame(closure.unchecked_ref())
+            .custom_expect("It's not possible request an animation frame".to_owned());
+    }
+
+    /// A function called on every frame, stops when the function returns false or when dropped.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let mut frames = 0;
+    /// let frame_loop = frame_loop(move |_timestamp| {
+    ///     frames += 1;
+    ///     frames < 60
+    /// });
+    /// ```
+    pub struct FrameLoop {
+        handle: Rc<RefCell<FrameHandle>>,
+    }
+
+    struct FrameHandle {
+        id: Option<i32>,
+        closure: Option<Closure<dyn FnMut(f64)>>,
+    }
+
+    /// Start a FrameLoop, the function receives the timestamp of the frame.
+    pub fn frame_loop<F>(mut callback: F) -> FrameLoop
+    where
+        F: FnMut(f64) -> bool + 'static,
+    {
+        let handle = Rc::new(RefCell::new(FrameHandle { id: None, closure: None }));
+        let weak = Rc::downgrade(&handle);
+        let closure = Closure::<dyn FnMut(f64)>::new(move |timestamp| {
+            let Some(handle) = weak.upgrade() else { return };
+            handle.borrow_mut().id = None;
+            if callback(timestamp) {
+                let id = handle.borrow().closure.as_ref().map(|c| {
+                    crate::window()
+                        .request_animation_frame(c.as_ref().unchecked_ref())
+                        .custom_expect("It's not possible request an animation frame".to_owned())
+                });
+                handle.borrow_mut().id = id;
+            }
+        });
+        let id = crate::window()
+            .request_animation_frame(closure.as_ref().unchecked_ref())
+            .custom_expect("It's not possible request an animation frame".to_owned());
+        {
+            let mut h = handle.borrow_mut();
+            h.id = Some(id);
+            h.closure = Some(closure);
+        }
+        FrameLoop { handle }
+    }
+
+    impl FrameLoop {
+        /// Check if the loop is still running.
+        pub fn is_running(&self) -> bool {
+            self.handle.borrow().id.is_some()
+        }
+        /// Stop the loop.
+        pub fn stop(&self) {
+            if let Some(id) = self.handle.borrow_mut().id.take() {
+                let _ = crate::window().cancel_animation_frame(id);
+            }
+        }
+    }
+
+    impl Drop for FrameLoop {
+        fn drop(&mut self) {
+            self.stop();
+        }
+    }
+
+    /// How many times an animation is played after the first time.
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    pub enum Repeat {
+        Times(u32),
+        Forever,
+    }
+
+    impl Default for Repeat {
+        fn default() -> Self {
+            Repeat::Times(0)
+        }
+    }
+
+    /// Options of an Animation, times are in milliseconds.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let options = AnimationOptions {
+    ///     duration: 600.0,
+    ///     easing: Easing::OutBack,
+    ///     repeat: Repeat::Times(2),
+    ///     yoyo: true,
+    ///     ..Default::default()
+    /// };
+    /// ```
+    #[derive(Clone, Copy, Debug, PartialEq)]
+    pub struct AnimationOptions {
+        pub duration: f64,
+        pub delay: f64,
+        pub easing: Easing,
+        pub repeat: Repeat,
+        /// Play every other repetition backwards.
+        pub yoyo: bool,
+    }
+
+    impl Default for AnimationOptions {
+        fn default() -> Self {
+            AnimationOptions {
+                duration: 300.0,
+                delay: 0.0,
+                easing: Easing::EASE,
+                repeat: Repeat::Times(0),
+                yoyo: false,
+            }
+        }
+    }
+
+    /// State of an animation at a given time, see AnimationOptions::sample.
+    #[derive(Clone, Copy, Debug, PartialEq)]
+    pub struct Sample {
+        /// Progress of the current repetition between 0 and 1, yoyo already applied.
+        pub progress: f64,
+        /// Eased progress, use it for interpolate.
+        pub value: f64,
+        /// Current repetition, starts from 0.
+        pub iteration: u32,
+        pub finished: bool,
+    }
+
+    impl AnimationOptions {
+        /// Get the duration with all the repetitions and without delay, None if it repeats forever.
+        pub fn active_duration(&self) -> Option<f64> {
+            match self.repeat {
+                Repeat::Times(n) => Some(self.duration.max(0.0) * (n as f64 + 1.0)),
+                Repeat::Forever => None,
+            }
+        }
+
+        /// Get the state of the animation after elapsed milliseconds.
+        ///
+        /// # Examples
+        ///
+        /// ```ignore
+        /// let options = AnimationOptions { duration: 100.0, easing: Easing::Linear, ..Default::default() };
+        /// assert_eq!(0.5, options.sample(50.0).value);
+        /// assert!(options.sample(100.0).finished);
+        /// ```
+        pub fn sample(&self, elapsed: f64) -> Sample {
+            let time = (elapsed - self.delay).max(0.0);
+            let duration = self.duration.max(0.0);
+            let iterations = match self.repeat {
+                Repeat::Times(n) => Some(n as f64 + 1.0),
+                Repeat::Forever => None,
+            };
+            let (iteration, progress, finished) = if duration == 0.0 {
+                let last = iterations.map(|n| n - 1.0).unwrap_or(0.0);
+                (last, 1.0, elapsed >= self.delay)
+            } else {
+                let current = (time / duration).floor();
+                match iterations {
+                    Some(n) if current >= n => (n - 1.0, 1.0, true),
+                    _ => (current, (time - current * duration) / duration, false),
+                }
+            };
+            let iteration = iteration as u32;
+            let progress = if self.yoyo && iteration % 2 == 1 { 1.0 - progress } else { progress };
+            Sample {
+                progress,
+                value: self.easing.apply(progress),
+                iteration,
+                finished,
+            }
+        }
+    }
+
+    /// Future resolved when an animation finishes or is cancelled.
+    pub struct Finished {
+        state: Rc<RefCell<FinishedState>>,
+    }
+
+    #[derive(Default)]
+    struct FinishedState {
+        done: bool,
+        wakers: Vec<Waker>,
+    }
+
+    impl FinishedState {
+        fn resolve(&mut self) {
+            self.done = true;
+            for waker in self.wakers.drain(..) {
+                waker.wake();
+            }
+        }
+    }
+
+    impl Future for Finished {
+        type Output = ();
+
+        fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+            let mut state = self.state.borrow_mut();
+            if state.done {
+                Poll::Ready(())
+            } else {
+                state.wakers.push(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+
+    /// An animation driven by requestAnimationFrame, cheap to clone.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use minimal_web::animation::*;
+    ///
+    /// let box_html = document().query_selector_html(".box");
+    /// let animation = Animation::new(0.0, 300.0, AnimationOptions::default(), move |x: f64| {
+    ///     box_html.set_prop("transform", &format!("translateX({x}px)"));
+    /// });
+    /// animation.play();
+    /// wasm_bindgen_futures::spawn_local(async move {
+    ///     animation.finished().await;
+    /// });
+    /// ```
+    #[derive(Clone)]
+    #[must_use = "the animation stops when dropped"]
+    pub struct Animation {
+        state: Rc<RefCell<AnimationState>>,
+    }
+
+    struct AnimationState {
+        options: AnimationOptions,
+        elapsed: f64,
+        last: Option<f64>,
+        frame: Option<FrameLoop>,
+        on_update: Option<Box<dyn FnMut(f64)>>,
+        on_complete: Vec<Box<dyn FnOnce()>>,
+        finished: Rc<RefCell<FinishedState>>,
+    }
+
+    impl Animation {
+        /// Create an animation from a value to another, on_update receives the interpolated value.
+        /// It doesn't start until play() is called.
+        pub fn new<T, F>(from: T, to: T, options: AnimationOptions, mut on_update: F) -> Animation
+        where
+            T: Lerp + 'static,
+            F: FnMut(T) + 'static,
+        {
+            Animation::with_progress(options, move |t| on_update(from.lerp(&to, t)))
+        }
+
+        /// Create an animation, on_update receives the eased progress.
+        pub fn with_progress<F>(options: AnimationOptions, on_update: F) -> Animation
+        where
+            F: FnMut(f64) + 'static,
+        {
+            Animation {
+                state: Rc::new(RefCell::new(AnimationState {
+                    options,
+                    elapsed: 0.0,
+                    last: None,
+                    frame: None,
+                    on_update: Some(Box::new(on_update)),
+                    on_complete: Vec::new(),
+                    finished: Rc::new(RefCell::new(FinishedState::default())),
+                })),
+            }
+        }
+
+        /// Get the options of the animation.
+        pub fn options(&self) -> AnimationOptions {
+            self.state.borrow().options
+        }
+
+        /// Start or resume the animation, if it's finished it starts again.
+        /// With reduced motion it goes to the end at once.
+        pub fn play(&self) {
+            if self.is_running() {
+                return;
+            }
+            {
+                let mut state = self.state.borrow_mut();
+                if state.options.sample(state.elapsed).finished && state.elapsed > 0.0 {
+                    state.elapsed = 0.0;
+                }
+                if state.finished.borrow().done {
+                    state.finished = Rc::new(RefCell::new(FinishedState::default()));
+                }
+                state.last = None;
+            }
+            if reduced_motion() {
+                //infinite animations stay on their first frame
+                match self.options().active_duration() {
+                    Some(_) => self.finish(),
+                    None => self.seek(0.0),
+                }
+                return;
+            }
+            let weak = Rc::downgrade(&self.state);
+            let frame = frame_loop(move |timestamp| Animation::tick(&weak, timestamp));
+            self.state.borrow_mut().frame = Some(frame);
+        }
+
+        /// Pause the animation, play() resumes it.
+        pub fn pause(&self) {
+            let frame = self.state.borrow_mut().frame.take();
+            drop(frame);
+        }
+
+        /// Same as play().
+        pub fn resume(&self) {
+            self.play();
+        }
+
+        /// Check if the animation is playing.
+        pub fn is_running(&self) -> bool {
+            self.state.borrow().frame.as_ref().map(|f| f.is_running()).unwrap_or(false)
+        }
+
+        /// Get the progress of the whole animation between 0 and 1, for infinite animations the progress of the current repetition.
+        pub fn progress(&self) -> f64 {
+            let state = self.state.borrow();
+            let time = (state.elapsed - state.options.delay).max(0.0);
+            match state.options.active_duration() {
+                Some(total) if total > 0.0 => (time / total).min(1.0),
+                Some(_) => if state.elapsed >= state.options.delay { 1.0 } else { 0.0 },
+                None => state.options.sample(state.elapsed).progress,
+            }
+        }
+
+        /// Go to a progress between 0 and 1 and update the value, it keeps playing if it was playing.
+        pub fn seek(&self, progress: f64) {
+            {
+                let mut state = self.state.borrow_mut();
+                let span = state.options.active_duration().unwrap_or(state.options.duration);
+                state.elapsed = state.options.delay + span * progress.clamp(0.0, 1.0);
+                state.last = None;
+            }
+            let sample = {
+                let state = self.state.borrow();
+                state.options.sample(state.elapsed)
+            };
+            Animation::emit(&self.state, sample.value);
+        }
+
+        /// Stop the animation where it is, finished() is resolved.
+        pub fn cancel(&self) {
+            self.pause();
+            let finished = self.state.borrow().finished.clone();
+            finished.borrow_mut().resolve();
+        }
+
+        /// Stop the animation and go to the end.
+        pub fn finish(&self) {
+            self.pause();
+            self.seek(1.0);
+            Animation::complete(&self.state);
+        }
+
+        /// Call a function when the animation ends.
+        pub fn on_complete<F>(&self, callback: F)
+        where
+            F: FnOnce() + 'static,
+        {
+            self.state.borrow_mut().on_complete.push(Box::new(callback));
+        }
+
+        /// Get a future resolved when the animation ends or is cancelled.
+        pub fn finished(&self) -> Finished {
+            Finished {
+                state: self.state.borrow().finished.clone(),
+            }
+        }
+
+        fn tick(weak: &Weak<RefCell<AnimationState>>, timestamp: f64) -> bool {
+            let Some(state) = weak.upgrade() else { return false };
+            let sample = {
+                let mut s = state.borrow_mut();
+                let delta = s.last.map(|last| timestamp - last).unwrap_or(0.0);
+                s.last = Some(timestamp);
+                s.elapsed += delta;
+                s.options.sample(s.elapsed)
+            };
+            Animation::emit(&state, sample.value);
+            if sample.finished {
+                Animation::complete(&state);
+                false
+            } else {
+                true
+            }
+        }
+
+        fn emit(state: &Rc<RefCell<AnimationState>>, value: f64) {
+            //taken out so on_update can call the animation without a double borrow
+            let callback = state.borrow_mut().on_update.take();
+            if let Some(mut callback) = callback {
+                callback(value);
+                state.borrow_mut().on_update.get_or_insert(callback);
+            }
+        }
+
+        fn complete(state: &Rc<RefCell<AnimationState>>) {
+            let (frame, callbacks, finished) = {
+                let mut s = state.borrow_mut();
+                (s.frame.take(), std::mem::take(&mut s.on_complete), s.finished.clone())
+            };
+            drop(frame);
+            for callback in callbacks {
+                callback();
+            }
+            finished.borrow_mut().resolve();
+        }
+    }
+
+    /// Create an Animation and play it.
+    pub fn animate<T, F>(from: T, to: T, options: AnimationOptions, on_update: F) -> Animation
+    where
+        T: Lerp + 'static,
+        F: FnMut(T) + 'static,
+    {
+        let animation = Animation::new(from, to, options, on_update);
+        animation.play();
+        animation
+    }
+
+    /// Default style of the .wrapper .glow structure of use_parallax, your css always wins on it.
+    pub const PARALLAX_CSS: &str = "\
+:where(.parallax-effect){position:relative;}\
+:where(.parallax-effect .wrapper){position:absolute;inset:0;overflow:hidden;border-radius:inherit;pointer-events:none;}\
+:where(.parallax-effect .glow){position:absolute;left:50%;top:50%;width:240px;height:240px;margin:-120px 0 0 -120px;border-radius:50%;background:radial-gradient(circle,rgba(255,255,255,.35) 0%,rgba(255,255,255,0) 70%);opacity:0;transition:opacity .3s ease;}";
+
+    /// An animation without its own frame loop, used by Timeline.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let title = document().query_selector_html("h1");
+    /// let tween = Tween::new(0.0, 1.0, AnimationOptions::default(), move |v: f64| {
+    ///     title.set_prop("opacity", &v.to_string());
+    /// });
+    /// ```
+    pub struct Tween {
+        options: AnimationOptions,
+        on_update: Box<dyn FnMut(f64)>,
+    }
+
+    impl Tween {
+        /// Create a tween from a value to another, on_update receives the interpolated value.
+        pub fn new<T, F>(from: T, to: T, options: AnimationOptions, mut on_update: F) -> Tween
+        where
+            T: Lerp + 'static,
+            F: FnMut(T) + 'static,
+        {
+            Tween::with_progress(options, move |t| on_update(from.lerp(&to, t)))
+        }
+
+        /// Create a tween, on_update receives the eased progress.
+        pub fn with_progress<F>(mut options: AnimationOptions, on_update: F) -> Tween
+        where
+            F: FnMut(f64) + 'static,
+        {
+            //a timeline needs an end
+            if options.repeat == Repeat::Forever {
+                options.repeat = Repeat::Times(0);
+            }
+            Tween {
+                options,
+                on_update: Box::new(on_update),
+            }
+        }
+
+        /// Create a tween of a style property, format converts the value to css.
+        pub fn style<F>(element: &HtmlElement, property: &str, from: f64, to: f64, options: AnimationOptions, format: F) -> Tween
+        where
+            F: Fn(f64) -> String + 'static,
+        {
+            let element = element.clone();
+            let property = property.to_owned();
+            Tween::new(from, to, options, move |v: f64| element.set_prop(&property, &format(v)))
+        }
+
+        /// Get the duration with delay and repetitions.
+        pub fn duration(&self) -> f64 {
+            self.options.delay + self.options.active_duration().unwrap_or(self.options.duration)
+        }
+    }
+
+    impl From<Timeline> for Tween {
+        fn from(timeline: Timeline) -> Tween {
+            let options = AnimationOptions {
+                duration: timeline.duration(),
+                easing: Easing::Linear,
+                ..Default::default()
+            };
+            Tween::with_progress(options, move |t| timeline.seek(t))
+        }
+    }
+
+    /// Where a Tween starts in a Timeline.
+    #[derive(Clone, Debug, PartialEq)]
+    pub enum Position {
+        /// After the end of the timeline.
+        End,
+        /// Same start of the previous tween.
+        WithPrevious,
+        /// Milliseconds from the start of the timeline.
+        At(f64),
+        /// Milliseconds from the end of the timeline, can be negative for overlap.
+        Offset(f64),
+        /// Milliseconds from a label.
+        Label(String, f64),
+    }
+
+    /// A sequence of tweens controlled as one animation, cheap to clone.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let header = document().query_selector_html("header");
+    /// let cards = document().query_selector_list(".card");
+    /// let cta = document().query_selector_html(".cta");
+    /// let fade = AnimationOptions { duration: 400.0, ..Default::default() };
+    ///
+    /// let timeline = Timeline::new();
+    /// timeline
+    ///     .add(Tween::style(&header, "opacity", 0.0, 1.0, fade, |v| v.to_string()))
+    ///     .label("cards")
+    ///     .add(stagger(&cards, 50.0, move |card, _| Tween::style(&card, "opacity", 0.0, 1.0, fade, |v| v.to_string())))
+    ///     .add_at(Position::Label("cards".to_owned(), 200.0), Tween::style(&cta, "opacity", 0.0, 1.0, fade, |v| v.to_string()))
+    ///     .on_progress(|p| gloo::console::log!(p));
+    /// timeline.play();
+    /// ```
+    #[derive(Clone)]
+    #[must_use = "the animation stops when dropped"]
+    pub struct Timeline {
+        state: Rc<RefCell<TimelineState>>,
+    }
+
+    struct TimelineItem {
+        start: f64,
+        options: AnimationOptions,
+        on_update: Option<Box<dyn FnMut(f64)>>,
+        last: Option<f64>,
+    }
+
+    struct TimelineState {
+        items: Vec<TimelineItem>,
+        labels: Vec<(String, f64)>,
+        time: f64,
+        direction: f64,
+        last: Option<f64>,
+        frame: Option<FrameLoop>,
+        on_progress: Vec<Box<dyn FnMut(f64)>>,
+        on_complete: Vec<Box<dyn FnMut()>>,
+        finished: Rc<RefCell<FinishedState>>,
+        //the frame loop keeps the timeline until it ends or is paused, for flip
+        keep_alive: bool,
+    }
+
+    impl Default for Timeline {
+        fn default() -> Self {
+            Timeline::new()
+        }
+    }
+
+    impl Timeline {
+        pub fn new() -> Timeline {
+            Timeline {
+                state: Rc::new(RefCell::new(TimelineState {
+                    items: Vec::new(),
+                    labels: Vec::new(),
+                    time: 0.0,
+                    direction: 1.0,
+                    last: None,
+                    frame: None,
+                    on_progress: Vec::new(),
+                    on_complete: Vec::new(),
+                    finished: Rc::new(RefCell::new(FinishedState::default())),
+                    keep_alive: false,
+                })),
+            }
+        }
+
+        /// Add a tween after the end of the timeline.
+        pub fn add<T: Into<Tween>>(&self, tween: T) -> &Self {
+            self.add_at(Position::End, tween)
+        }
+
+        /// Add a tween that starts with the previous one.
+        pub fn with<T: Into<Tween>>(&self, tween: T) -> &Self {
+            self.add_at(Position::WithPrevious, tween)
+        }
+
+        /// Add a tween at a position.
+        pub fn add_at<T: Into<Tween>>(&self, position: Position, tween: T) -> &Self {
+            let tween = tween.into();
+            let start = self.resolve(&position);
+            self.state.borrow_mut().items.push(TimelineItem {
+                start,
+                options: tween.options,
+                on_update: Some(tween.on_update),
+                last: None,
+            });
+            self
+        }
+
+        /// Add a label at the end of the timeline, use it with Position::Label.
+        pub fn label(&self, name: &str) -> &Self {
+            let end = self.duration();
+            self.label_at(name, end)
+        }
+
+        /// Add a label at milliseconds from the start.
+        pub fn label_at(&self, name: &str, time: f64) -> &Self {
+            let mut state = self.state.borrow_mut();
+            state.labels.retain(|(n, _)| n != name);
+            state.labels.push((name.to_owned(), time.max(0.0)));
+            self
+        }
+
+        /// Call a function on every frame with the progress between 0 and 1.
+        pub fn on_progress<F: FnMut(f64) + 'static>(&self, callback: F) -> &Self {
+            self.state.borrow_mut().on_progress.push(Box::new(callback));
+            self
+        }
+
+        /// Call a function every time the timeline reaches its end (or its start when reversed).
+        pub fn on_complete<F: FnMut() + 'static>(&self, callback: F) -> &Self {
+            self.state.borrow_mut().on_complete.push(Box::new(callback));
+            self
+        }
+
+        fn resolve(&self, position: &Position) -> f64 {
+            let state = self.state.borrow();
+            let end = state.items.iter().map(|i| i.start + item_duration(&i.options)).fold(0.0, f64::max);
+            let start = match position {
+                Position::End => end,
+                Position::WithPrevious => state.items.last().map(|i| i.start).unwrap_or(0.0),
+                Position::At(time) => *time,
+                Position::Offset(offset) => end + offset,
+                Position::Label(name, offset) => {
+                    let label = state.labels.iter().find(|(n, _)| n == name).map(|(_, t)| *t);
+                    label.custom_expect("There's not a label in the timeline : ".to_owned() + name) + offset
+                }
+            };
+            start.max(0.0)
+        }
+
+        /// Get the duration of the timeline in milliseconds.
+        pub fn duration(&self) -> f64 {
+            let state = self.state.borrow();
+            state.items.iter().map(|i| i.start + item_duration(&i.options)).fold(0.0, f64::max)
+        }
+
+        /// Get the progress between 0 and 1.
+        pub fn progress(&self) -> f64 {
+            let duration = self.duration();
+            if duration > 0.0 {
+                (self.state.borrow().time / duration).clamp(0.0, 1.0)
+            } else {
+                1.0
+            }
+        }
+
+        /// Play forward from where it is, from the start if it's at the end.
+        /// With reduced motion it goes to the end at once.
+        pub fn play(&self) {
+            self.state.borrow_mut().direction = 1.0;
+            self.start();
+        }
+
+        /// Play backward from where it is, from the end if it's at the start.
+        pub fn reverse(&self) {
+            self.state.borrow_mut().direction = -1.0;
+            self.start();
+        }
+
+        /// Check if the timeline is playing backward.
+        pub fn is_reversed(&self) -> bool {
+            self.state.borrow().direction < 0.0
+        }
+
+        pub fn pause(&self) {
+            let frame = self.state.borrow_mut().frame.take();
+            drop(frame);
+        }
+
+        pub fn is_running(&self) -> bool {
+            self.state.borrow().frame.as_ref().map(|f| f.is_running()).unwrap_or(false)
+        }
+
+        /// Go to a progress between 0 and 1 and update every tween.
+        pub fn seek(&self, progress: f64) {
+            let duration = self.duration();
+            {
+                let mut state = self.state.borrow_mut();
+                state.time = duration * progress.clamp(0.0, 1.0);
+                state.last = None;
+            }
+            Timeline::render(&self.state);
+        }
+
+        /// Get a future resolved when the timeline reaches its end (or its start when reversed).
+        pub fn finished(&self) -> Finished {
+            Finished {
+                state: self.state.borrow().finished.clone(),
+            }
+        }
+
+        fn start(&self) {
+            let duration = self.duration();
+            let reduced = reduced_motion();
+            {
+                let mut state = self.state.borrow_mut();
+                if state.direction > 0.0 && state.time >= duration {
+                    state.time = 0.0;
+                } else if state.direction < 0.0 && state.time <= 0.0 {
+                    state.time = duration;
+                }
+                if state.finished.borrow().done {
+                    state.finished = Rc::new(RefCell::new(FinishedState::default()));
+                }
+                state.last = None;
+                if state.frame.is_some() {
+                    return;
+                }
+                if reduced {
+                    state.time = if state.direction > 0.0 { duration } else { 0.0 };
+                }
+            }
+            if reduced {
+                Timeline::render(&self.state);
+                Timeline::complete(&self.state);
+                return;
+            }
+            let strong = self.state.borrow().keep_alive.then(|| self.state.clone());
+            let weak = Rc::downgrade(&self.state);
+            let frame = frame_loop(move |timestamp| {
+                let Some(state) = strong.clone().or_else(|| weak.upgrade()) else { return false };
+                let done = {
+                    let mut s = state.borrow_mut();
+                    let duration = s.items.iter().map(|i| i.start + item_duration(&i.options)).fold(0.0, f64::max);
+                    let delta = s.last.map(|last| timestamp - last).unwrap_or(0.0);
+                    s.last = Some(timestamp);
+                    s.time = (s.time + delta * s.direction).clamp(0.0, duration);
+                    (s.direction > 0.0 && s.time >= duration) || (s.direction < 0.0 && s.time <= 0.0)
+                };
+                Timeline::render(&state);
+                if done {
+                    Timeline::complete(&state);
+                }
+                !done
+            });
+            self.state.borrow_mut().frame = Some(frame);
+        }
+
+        fn render(state: &Rc<RefCell<TimelineState>>) {
+            let (time, count) = {
+                let s = state.borrow();
+                (s.time, s.items.len())
+            };
+            for index in 0..count {
+                //callbacks are taken out so they can use the timeline
+                let (value, callback) = {
+                    let mut s = state.borrow_mut();
+                    let item = &mut s.items[index];
+                    let value = item.options.sample(time - item.start).value;
+                    if item.last == Some(value) {
+                        continue;
+                    }
+                    item.last = Some(value);
+                    (value, item.on_update.take())
+                };
+                if let Some(mut callback) = callback {
+                    callback(value);
+                    state.borrow_mut().items[index].on_update.get_or_insert(callback);
+                }
+            }
+            let duration = {
+                let s = state.borrow();
+                s.items.iter().map(|i| i.start + item_duration(&i.options)).fold(0.0, f64::max)
+            };
+            let progress = if duration > 0.0 { (time / duration).clamp(0.0, 1.0) } else { 1.0 };
+            let mut callbacks = std::mem::take(&mut state.borrow_mut().on_progress);
+            for callback in callbacks.iter_mut() {
+                callback(progress);
+            }
+            let mut s = state.borrow_mut();
+            callbacks.append(&mut s.on_progress);
+            s.on_progress = callbacks;
+        }
+
+        fn complete(state: &Rc<RefCell<TimelineState>>) {
+            let (frame, mut callbacks, finished) = {
+                let mut s = state.borrow_mut();
+                (s.frame.take(), std::mem::take(&mut s.on_complete), s.finished.clone())
+            };
+            drop(frame);
+            for callback in callbacks.iter_mut() {
+                callback();
+            }
+            {
+                let mut s = state.borrow_mut();
+                callbacks.append(&mut s.on_complete);
+                s.on_complete = callbacks;
+            }
+            finished.borrow_mut().resolve();
+        }
+    }
+
+    fn item_duration(options: &AnimationOptions) -> f64 {
+        options.delay + options.active_duration().unwrap_or(options.duration)
+    }
+
+    /// Create a Timeline where every element of list starts each milliseconds after the previous one.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let cards = document().query_selector_list(".card");
+    /// let options = AnimationOptions { duration: 300.0, ..Default::default() };
+    /// //keep the timeline until it ends, it stops when dropped
+    /// let timeline = stagger(&cards, 50.0, move |card, _index| {
+    ///     Tween::style(&card, "opacity", 0.0, 1.0, options, |v| v.to_string())
+    /// });
+    /// timeline.play();
+    /// timeline.finished().await;
+    /// ```
+    #[must_use = "the animation stops when dropped"]
+    pub fn stagger<F>(list: &NodeList, each: f64, mut tween: F) -> Timeline
+    where
+        F: FnMut(HtmlElement, u32) -> Tween,
+    {
+        let timeline = Timeline::new();
+        for i in 0..list.length() {
+            timeline.add_at(Position::At(each * i as f64), tween(list.get_html(i), i));
+        }
+        timeline
+    }
+
+    /// Options of flip_with and use_flip_with.
+    #[derive(Clone, Copy, Debug, PartialEq)]
+    pub struct FlipOptions {
+        pub duration: f64,
+        pub easing: Easing,
+        /// Animate the size too, false only moves the elements.
+        pub scale: bool,
+        /// Milliseconds between the start of an element and the next one.
+        pub stagger: f64,
+    }
+
+    impl Default for FlipOptions {
+        fn default() -> Self {
+            FlipOptions {
+                duration: 300.0,
+                easing: Easing::OutCubic,
+                scale: true,
+                stagger: 0.0,
+            }
+        }
+    }
+
+    /// Position and size of an element before a change of layout, see flip_from.
+    #[derive(Clone, Copy, Debug, PartialEq)]
+    pub struct FlipRect {
+        pub left: f64,
+        pub top: f64,
+        pub width: f64,
+        pub height: f64,
+    }
+
+    impl FlipRect {
+        /// Measure element now, with its current transform.
+        pub fn measure(element: &Element) -> FlipRect {
+            let rect = element.get_bounding_client_rect();
+            FlipRect {
+                left: rect.left(),
+                top: rect.top(),
+                width: rect.width(),
+                height: rect.height(),
+            }
+        }
+    }
+
+    /// Animate elements from where they were before a change of layout to where they are now.
+    /// first has the rect of every element before the change, elements that didn't move or aren't in the page are skipped.
+    /// The timeline keeps playing when it's dropped, it can be kept to pause it or wait for the end.
+    #[must_use = "the timeline can pause the flip or wait for its end, it keeps playing when dropped"]
+    pub fn flip_from(first: Vec<(HtmlElement, FlipRect)>, options: FlipOptions) -> Timeline {
+        let timeline = Timeline::new();
+        let mut index = 0.0;
+        for (element, first) in first {
+            if !element.is_connected() {
+                continue;
+            }
+            let last = FlipRect::measure(&element);
+            let (dx, dy) = (first.left - last.left, first.top - last.top);
+            let (sx, sy) = match options.scale {
+                true if last.width > 0.0 && last.height > 0.0 => (first.width / last.width, first.height / last.height),
+                _ => (1.0, 1.0),
+            };
+            if dx.abs() < 0.5 && dy.abs() < 0.5 && (sx - 1.0).abs() < 0.001 && (sy - 1.0).abs() < 0.001 {
+                continue;
+            }
+            //the inline transform of the element is kept after the flip transform, and restored at the end
+            let transform = element.get_prop("transform");
+            let origin = element.get_prop("transform-origin");
+            let easing = options.easing;
+            let tween_options = AnimationOptions {
+                duration: options.duration,
+                delay: 0.0,
+                easing: Easing::Linear,
+                repeat: Repeat::Times(0),
+                yoyo: false,
+            };
+            let tween = Tween::with_progress(tween_options, move |t| {
+                if t >= 1.0 {
+                    for (property, value) in [("transform", &transform), ("transform-origin", &origin)] {
+                        match value.is_empty() {
+                            true => drop(element.remove_prop(property)),
+                            false => element.set_prop(property, value),
+                        }
+                    }
+                    return;
+                }
+                let k = 1.0 - easing.apply(t);
+                element.set_prop("transform-origin", "0 0");
+                element.set_prop(
+                    "transform",
+                    &format!(
+                        "translate({:.2}px, {:.2}px) scale({:.4}, {:.4}) {transform}",
+                        dx * k,
+                        dy * k,
+                        1.0 + (sx - 1.0) * k,
+                        1.0 + (sy - 1.0) * k,
+                    ),
+                );
+            });
+            timeline.add_at(Position::At(options.stagger * index), tween);
+            index += 1.0;
+        }
+        timeline.state.borrow_mut().keep_alive = true;
+        //the first frame is written now, so the new layout is never shown before the animation
+        timeline.seek(0.0);
+        timeline.play();
+        timeline
+    }
+
+    /// Same as flip with options.
+    #[must_use = "the timeline can pause the flip or wait for its end, it keeps playing when dropped"]
+    pub fn flip_with<F>(elements: &NodeList, options: FlipOptions, mutate: F) -> Timeline
+    where
+        F: FnOnce(),
+    {
+        let first = (0..elements.length())
+            .map(|i| {
+                let element = elements.get_html(i);
+                let rect = FlipRect::measure(&element);
+                (element, rect)
+            })
+            .collect();
+        mutate();
+        flip_from(first, options)
+    }
+
+    /// Record the position of elements, run mutate and animate the elements from their old position to the new one.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let list = document().query_selector_html("ul");
+    /// let items = list.query_selector_list("li");
+    /// let timeline = flip(&items, || {
+    ///     let first = list.first_element_child_el();
+    ///     list.app_child(first.into());
+    /// });
+    /// timeline.finished().await;
+    /// ```
+    #[must_use = "the timeline can pause the flip or wait for its end, it keeps playing when dropped"]
+    pub fn flip<F>(elements: &NodeList, mutate: F) -> Timeline
+    where
+        F: FnOnce(),
+    {
+        flip_with(elements, FlipOptions::default(), mutate)
+    }
+
+    /// Animate the children of container with data-flip-key when they move after a render.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let list = use_node_ref();
+    /// use_flip(list.clone());
+    /// html!{
+    ///     <ul ref={list}>
+    ///         { for items.iter().map(|item| html!{ <li key={item.id} data-flip-key={item.id.to_string()}>{ &item.name }</li> }) }
+    ///     </ul>
+    /// }
+    /// ```
+    #[hook]
+    pub fn use_flip(container: NodeRef) {
+        use_flip_with(container, FlipOptions::default());
+    }
+
+    /// Same as use_flip with options.
+    #[hook]
+    pub fn use_flip_with(container: NodeRef, options: FlipOptions) {
+        let first = use_mut_ref(Vec::<(String, FlipRect)>::new);
+        let running = use_mut_ref(|| None::<Timeline>);
+        //measured while rendering, before Yew changes the page
+        if let Some(container) = container.cast::<Element>() {
+            let children = container.query_selector_list("[data-flip-key]");
+            *first.borrow_mut() = (0..children.length())
+                .map(|i| {
+                    let child = children.get_el(i);
+                    (child.get_attr("data-flip-key"), FlipRect::measure(&child))
+                })
+                .collect();
+        }
+        use_effect(move || {
+            if let Some(old) = running.borrow_mut().take() {
+                old.pause();
+                old.seek(1.0);
+            }
+            if let Some(container) = container.cast::<Element>() {
+                let first = std::mem::take(&mut *first.borrow_mut());
+                let children = container.query_selector_list("[data-flip-key]");
+                let moved = (0..children.length())
+                    .filter_map(|i| {
+                        let child = children.get_html(i);
+                        let key = child.get_attr("data-flip-key");
+                        first.iter().find(|(k, _)| *k == key).map(|(_, rect)| (child, *rect))
+                    })
+                    .collect();
+                *running.borrow_mut() = Some(flip_from(moved, options));
+            }
+            || ()
+        });
+    }
+
+    /// A value moved by a Spring on every frame, cheap to clone.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let card = document().query_selector_html(".card");
+    /// let scale = SpringAnimation::on_style(&card, "scale", Spring::WOBBLY, 1.0, |v| v.to_string());
+    /// scale.set_target(1.2);
+    /// ```
+    pub struct SpringAnimation<T: SpringValue> {
+        state: Rc<RefCell<SpringDriver>>,
+        marker: std::marker::PhantomData<T>,
+    }
+
+    impl<T: SpringValue> Clone for SpringAnimation<T> {
+        fn clone(&self) -> Self {
+            SpringAnimation {
+                state: self.state.clone(),
+                marker: std::marker::PhantomData,
+            }
+        }
+    }
+
+    type SpringCallback = Box<dyn FnMut(&[f64])>;
+
+    struct SpringDriver {
+        spring: Spring,
+        state: SpringState,
+        last: Option<f64>,
+        frame: Option<FrameLoop>,
+        on_update: Option<SpringCallback>,
+    }
+
+    impl<T: SpringValue + 'static> SpringAnimation<T> {
+        /// Create a spring at rest on value, on_update is called on every frame while it moves.
+        pub fn new<F>(spring: Spring, value: T, mut on_update: F) -> SpringAnimation<T>
+        where
+            F: FnMut(T) + 'static,
+        {
+            SpringAnimation {
+                state: Rc::new(RefCell::new(SpringDriver {
+                    spring,
+                    state: SpringState::new(&value),
+                    last: None,
+                    frame: None,
+                    on_update: Some(Box::new(move |c| on_update(T::from_components(c)))),
+                })),
+                marker: std::marker::PhantomData,
+            }
+        }
+
+        /// Create a spring that writes a style property of element, format converts the value to css.
+        pub fn on_style<F>(element: &HtmlElement, property: &str, spring: Spring, value: T, format: F) -> SpringAnimation<T>
+        where
+            F: Fn(T) -> String + 'static,
+        {
+            let element = element.clone();
+            let property = property.to_owned();
+            SpringAnimation::new(spring, value, move |v| element.set_prop(&property, &format(v)))
+        }
+
+        /// Move toward a new target, the current velocity is kept.
+        /// With reduced motion it jumps to target and on_update is called once.
+        pub fn set_target(&self, target: T) {
+            if reduced_motion() {
+                self.set_value(target.clone());
+                let callback = self.state.borrow_mut().on_update.take();
+                if let Some(mut callback) = callback {
+                    callback(&target.components());
+                    self.state.borrow_mut().on_update.get_or_insert(callback);
+                }
+                return;
+            }
+            self.state.borrow_mut().state.set_target(&target);
+            self.start();
+        }
+
+        /// Jump to a value and stop, on_update is not called.
+        pub fn set_value(&self, value: T) {
+            let frame = {
+                let mut driver = self.state.borrow_mut();
+                driver.state.set_value(&value);
+                driver.frame.take()
+            };
+            drop(frame);
+        }
+
+        /// Change the spring, it's used from the next frame.
+        pub fn set_spring(&self, spring: Spring) {
+            self.state.borrow_mut().spring = spring;
+        }
+
+        /// Get the value now.
+        pub fn value(&self) -> T {
+            self.state.borrow().state.value()
+        }
+
+        /// Get the velocity now, in units per second.
+        pub fn velocity(&self) -> T {
+            T::from_components(&self.state.borrow().state.velocity)
+        }
+
+        /// Get the target.
+        pub fn target(&self) -> T {
+            T::from_components(&self.state.borrow().state.target)
+        }
+
+        /// Check if the spring is at rest.
+        pub fn is_at_rest(&self) -> bool {
+            self.state.borrow().frame.is_none()
+        }
+
+        /// Stop where it is, velocity is lost.
+        pub fn stop(&self) {
+            let value = self.value();
+            self.set_value(value);
+        }
+
+        fn start(&self) {
+            if self.state.borrow().frame.is_some() {
+                return;
+            }
+            self.state.borrow_mut().last = None;
+            let weak = Rc::downgrade(&self.state);
+            let frame = frame_loop(move |timestamp| {
+                let Some(driver) = weak.upgrade() else { return false };
+                let (rest, value, callback) = {
+                    let mut d = driver.borrow_mut();
+                    let dt = d.last.map(|last| timestamp - last).unwrap_or(1000.0 / 60.0);
+                    d.last = Some(timestamp);
+                    let spring = d.spring;
+                    let rest = d.state.step(&spring, dt);
+                    (rest, d.state.value.clone(), d.on_update.take())
+                };
+                if let Some(mut callback) = callback {
+                    callback(&value);
+                    driver.borrow_mut().on_update.get_or_insert(callback);
+                }
+                if rest {
+                    let frame = driver.borrow_mut().frame.take();
+                    drop(frame);
+                }
+                !rest
+            });
+            self.state.borrow_mut().frame = Some(frame);
+        }
+    }
+
+    /// Get a value that follows target with a spring, the component is updated on every frame while it moves.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let open = use_state(|| false);
+    /// let height = use_spring(if *open { 300.0 } else { 0.0 });
+    /// html!{ <div style={format!("height:{height}px")}></div> }
+    /// ```
+    #[hook]
+    pub fn use_spring<T>(target: T) -> T
+    where
+        T: SpringValue + PartialEq + 'static,
+    {
+        use_spring_with(Spring::default(), target)
+    }
+
+    /// Same as use_spring with a custom Spring.
+    #[hook]
+    pub fn use_spring_with<T>(spring: Spring, target: T) -> T
+    where
+        T: SpringValue + PartialEq + 'static,
+    {
+        let value = {
+            let target = target.clone();
+            use_state(move || target)
+        };
+        let animation = {
+            let value = value.clone();
+            let initial = target.clone();
+            use_mut_ref(move || SpringAnimation::new(spring, initial, move |v| value.set(v)))
+        };
+        animation.borrow().set_spring(spring);
+        {
+            let animation = animation.clone();
+            use_effect_with_deps(
+                move |target| {
+                    animation.borrow().set_target(target.clone());
+                    || ()
+                },
+                target,
+            );
+        }
+        {
+            use_effect_with_deps(
+                move |_| {
+                    move || animation.borrow().stop()
+                },
+                (),
+            );
+        }
+        (*value).clone()
+    }
+
+    /// Axis followed by the parallax tilt.
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+    pub enum ParallaxAxis {
+        #[default]
+        Both,
+        /// Only the horizontal movement of the mouse, the element rotates around its vertical axis.
+        X,
+        /// Only the vertical movement of the mouse, the element rotates around its horizontal axis.
+        Y,
+    }
+
+    impl ParallaxAxis {
+        /// Get an axis from "both", "x" or "y".
+        pub fn parse(value: &str) -> Option<ParallaxAxis> {
+            match value.trim().to_lowercase().as_str() {
+                "both" | "xy" => Some(ParallaxAxis::Both),
+                "x" => Some(ParallaxAxis::X),
+                "y" => Some(ParallaxAxis::Y),
+                _ => None,
+            }
+        }
+    }
+
+    /// Glow of the parallax, it follows the mouse.
+    #[derive(Clone, Debug, PartialEq)]
+    pub struct GlowOptions {
+        /// Selector of the glow inside the parallax element.
+        pub selector: String,
+        /// Opacity of the glow while the mouse is over.
+        pub opacity: f64,
+    }
+
+    impl Default for GlowOptions {
+        fn default() -> Self {
+            GlowOptions {
+                selector: ".wrapper .glow".to_owned(),
+                opacity: 1.0,
+            }
+        }
+    }
+
+    /// Options of use_parallax_with, every option can be changed per element with a data-* attribute.
+    ///
+    /// | option | attribute |
+    /// |---|---|
+    /// | max_tilt | data-max="10" |
+    /// | perspective | data-perspective="800" |
+    /// | axis | data-axis="both", "x" or "y" |
+    /// | invert | data-invert="true" |
+    /// | scale_on_hover | data-scale="1.05" |
+    /// | reset_duration | data-reset-duration="400", or "spring" |
+    /// | glow | data-glow="false", or the selector of the glow |
+    /// | glow opacity | data-glow-opacity="0.6" |
+    ///
+    /// The selector is the only option without attribute, it's how the elements are found.
+    #[derive(Clone, Debug, PartialEq)]
+    pub struct ParallaxOptions {
+        pub selector: String,
+        /// Max rotation in deg.
+        pub max_tilt: f64,
+        /// Perspective in px.
+        pub perspective: f64,
+        pub axis: ParallaxAxis,
+        /// Tilt toward the mouse instead of away from it.
+        pub invert: bool,
+        /// Scale while the mouse is over, 1.0 for no scale.
+        pub scale_on_hover: f64,
+        /// Duration in ms of the reset when the mouse leaves, None for a spring.
+        pub reset_duration: Option<f64>,
+        pub glow: Option<GlowOptions>,
+    }
+
+    impl Default for ParallaxOptions {
+        fn default() -> Self {
+            ParallaxOptions {
+                selector: ".parallax-effect".to_owned(),
+                max_tilt: 2.0,
+                perspective: 1000.0,
+                axis: ParallaxAxis::Both,
+                invert: false,
+                scale_on_hover: 1.0,
+                reset_duration: None,
+                glow: Some(GlowOptions::default()),
+            }
+        }
+    }
+
+    impl ParallaxOptions {
+        /// Get the options of an element, data-* attributes override these options.
+        pub fn for_element(&self, element: &Element) -> ParallaxOptions {
+            let attr = |name: &str| element.get_attribute(name).map(|v| v.trim().to_owned());
+            let number = |name: &str| attr(name).and_then(|v| v.parse::<f64>().ok());
+            let flag = |name: &str| attr(name).map(|v| v != "false");
+            let mut options = self.clone();
+            if let Some(max) = number("data-max") {
+                options.max_tilt = max;
+            }
+            if let Some(perspective) = number("data-perspective") {
+                options.perspective = perspective;
+            }
+            if let Some(axis) = attr("data-axis").and_then(|v| ParallaxAxis::parse(&v)) {
+                options.axis = axis;
+            }
+            if let Some(invert) = flag("data-invert") {
+                options.invert = invert;
+            }
+            if let Some(scale) = number("data-scale") {
+                options.scale_on_hover = scale;
+            }
+            if let Some(reset) = attr("data-reset-duration") {
+                options.reset_duration = reset.parse::<f64>().ok();
+            }
+            match attr("data-glow").as_deref() {
+                Some("false") => options.glow = None,
+                Some("true") | Some("") | None => {}
+                Some(selector) => {
+                    options.glow = Some(GlowOptions {
+                        selector: selector.to_owned(),
+                        ..options.glow.unwrap_or_default()
+                    });
+                }
+            }
+            if let (Some(glow), Some(opacity)) = (options.glow.as_mut(), number("data-glow-opacity")) {
+                glow.opacity = opacity;
+            }
+            options
+        }
+
+        /// Get rotate_x and rotate_y in deg for a mouse at (x, y) from the center of an element of size (width, height).
+        pub fn tilt(&self, x: f64, y: f64, width: f64, height: f64) -> (f64, f64) {
+            let sign = if self.invert { -1.0 } else { 1.0 };
+            let rotate_x = if height > 0.0 { sign * self.max_tilt * y / (height / 2.0) } else { 0.0 };
+            let rotate_y = if width > 0.0 { -sign * self.max_tilt * x / (width / 2.0) } else { 0.0 };
+            match self.axis {
+                ParallaxAxis::Both => (rotate_x, rotate_y),
+                ParallaxAxis::X => (0.0, rotate_y),
+                ParallaxAxis::Y => (rotate_x, 0.0),
+            }
+        }
+
+        fn transform(&self, (rotate_x, rotate_y, scale): (f64, f64, f64)) -> String {
+            format!("perspective({}px) rotateX({rotate_x}deg) rotateY({rotate_y}deg) scale({scale})", self.perspective)
+        }
+    }
+
+    //Inline styles of the elements touched by an effect, restored when the effect is dropped
+    #[derive(Default)]
+    struct StyleSnapshot {
+        saved: Vec<(HtmlElement, Option<String>)>,
+    }
+
+    impl StyleSnapshot {
+        fn save(&mut self, element: &HtmlElement) {
+            if !self.saved.iter().any(|(e, _)| e == element) {
+                self.saved.push((element.clone(), element.get_attribute("style")));
+            }
+        }
+
+        fn restore(&mut self) {
+            for (element, style) in self.saved.drain(..) {
+                match style {
+                    Some(style) => element.set_attr("style", &style),
+                    None => element.remove_attr("style"),
+                }
+            }
+        }
+    }
+
+    struct ParallaxElement {
+        element: HtmlElement,
+        spring: SpringAnimation<(f64, f64, f64)>,
+        reset: Option<Animation>,
+    }
+
+    struct ParallaxState {
+        options: ParallaxOptions,
+        elements: Vec<ParallaxElement>,
+        hovered: Option<HtmlElement>,
+        styles: StyleSnapshot,
+    }
+
+    impl ParallaxState {
+        //Get the state of a parallax element, created the first time
+        fn element(&mut self, element: &HtmlElement, options: &ParallaxOptions) -> &mut ParallaxElement {
+            let index = match self.elements.iter().position(|p| p.element == *element) {
+                Some(index) => index,
+                None => {
+                    self.styles.save(element);
+                    let transform_options = options.clone();
+                    let spring = SpringAnimation::on_style(element, "transform", Spring::GENTLE, (0.0, 0.0, 1.0), move |value| {
+                        transform_options.transform(value)
+                    });
+                    self.elements.push(ParallaxElement { element: element.clone(), spring, reset: None });
+                    self.elements.len() - 1
+                }
+            };
+            &mut self.elements[index]
+        }
+
+        fn glow(&mut self, element: &HtmlElement, options: &ParallaxOptions) -> Option<(HtmlElement, f64)> {
+            let glow = options.glow.as_ref()?;
+            let glow_html = element.query_selector(&glow.selector).ok().flatten()?.to_html();
+            self.styles.save(&glow_html);
+            Some((glow_html, glow.opacity))
+        }
+
+        fn on_move(&mut self, el_html: &HtmlElement, e: &web_sys::MouseEvent) {
+            //Animation calc
+            let options = self.options.for_element(el_html);
+            let rect = el_html.get_bounding_client_rect();
+            let width = el_html.offset_width() as f64;
+            let height = el_html.offset_height() as f64;
+            let mouse_x = ((e.client_x() as f64) - rect.left()) - (width / 2.0);
+            let mouse_y = ((e.client_y() as f64) - rect.top()) - (height / 2.0);
+            let (rotate_x, rotate_y) = options.tilt(mouse_x, mouse_y, width, height);
+            let value = (rotate_x, rotate_y, options.scale_on_hover);
+            //Stop the reset if the mouse is over again
+            let p = self.element(el_html, &options);
+            if let Some(reset) = p.reset.take() {
+                reset.pause();
+            }
+            p.spring.set_value(value);
+            //Animate element
+            el_html.set_prop("transform", &options.transform(value));
+            //Check if glow exist then animate
+            if let Some((glow, opacity)) = self.glow(el_html, &options) {
+                glow.set_prop("transform", &format!("translate({mouse_x}px,{mouse_y}px)"));
+                glow.set_prop("opacity", &opacity.to_string());
+            }
+        }
+
+        fn on_leave(&mut self, el_html: &HtmlElement) {
+            let options = self.options.for_element(el_html);
+            //Reset element with a spring or with a duration
+            let p = self.element(el_html, &options);
+            match options.reset_duration {
+                None => p.spring.set_target((0.0, 0.0, 1.0)),
+                Some(duration) => {
+                    let from = p.spring.value();
+                    p.spring.set_value((0.0, 0.0, 1.0));
+                    let target = el_html.clone();
+                    let reset_options = options.clone();
+                    p.reset = Some(animate(
+                        from,
+                        (0.0, 0.0, 1.0),
+                        AnimationOptions { duration, easing: Easing::OutCubic, ..Default::default() },
+                        move |value| target.set_prop("transform", &reset_options.transform(value)),
+                    ));
+                }
+            }
+            //Check if glow exist then reset animation
+            if let Some((glow, _)) = self.glow(el_html, &options) {
+                glow.set_prop("transform", "translate(0px,0px)");
+                glow.set_prop("opacity", "0");
+            }
+        }
+    }
+
+    /// Parallax of every element matching options.selector, also the ones added later.
+    /// When dropped the listeners are removed and the inline styles restored.
+    pub struct Parallax {
+        state: Rc<RefCell<ParallaxState>>,
+        _listeners: Vec<Listener>,
+    }
+
+    impl Drop for Parallax {
+        fn drop(&mut self) {
+            let mut state = self.state.borrow_mut();
+            for p in state.elements.drain(..) {
+                if let Some(reset) = p.reset {
+                    reset.pause();
+                }
+                p.spring.stop();
+            }
+            state.styles.restore();
+        }
+    }
+
+    /// Start a parallax without Yew, it lives until the returned Parallax is dropped.
+    pub fn parallax(options: ParallaxOptions) -> Parallax {
+        crate::style::inject_css("minimal-parallax-style", PARALLAX_CSS);
+        let state = Rc::new(RefCell::new(ParallaxState {
+            options,
+            elements: Vec::new(),
+            hovered: None,
+            styles: StyleSnapshot::default(),
+        }));
+        let document = crate::document();
+        //one delegated listener for every element, so elements added later work too
+        let state_move = state.clone();
+        let on_move = listen_passive(&document, "mousemove", move |e| {
+            if reduced_motion() {
+                return;
+            }
+            let Some(e) = e.dyn_ref::<web_sys::MouseEvent>() else { return };
+            let mut state = state_move.borrow_mut();
+            let selector = state.options.selector.clone();
+            let element = e
+                .target()
+                .and_then(|t| t.dyn_into::<Element>().ok())
+                .and_then(|t| t.closest(&selector).ok().flatten())
+                .and_then(|el| el.dyn_into::<HtmlElement>().ok());
+            if state.hovered != element {
+                if let Some(previous) = state.hovered.take() {
+                    state.on_leave(&previous);
+                }
+                state.hovered = element.clone();
+            }
+            if let Some(element) = element {
+                state.on_move(&element, e);
+            }
+        });
+        let state_out = state.clone();
+        let on_out = listen_passive(&document, "mouseout", move |e| {
+            let Some(e) = e.dyn_ref::<web_sys::MouseEvent>() else { return };
+            let mut state = state_out.borrow_mut();
+            let Some(hovered) = state.hovered.clone() else { return };
+            let inside = e
+                .related_target()
+                .and_then(|t| t.dyn_into::<web_sys::Node>().ok())
+                .map(|node| hovered.contains(Some(&node)))
+                .unwrap_or(false);
+            if !inside {
+                state.hovered = None;
+                state.on_leave(&hovered);
+            }
+        });
+        Parallax {
+            state,
+            _listeners: vec![on_move, on_out],
+        }
+    }
+
+    /// Same as use_parallax_with(ParallaxOptions::default()).
+    #[hook]
+    pub fn use_parallax() {
+        use_parallax_with(ParallaxOptions::default());
+    }
+
+    /// Tilt elements toward the mouse, see ParallaxOptions for the data-* attributes.
+    /// Elements rendered later are handled too, and everything is cleaned when the component is unmounted.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use_parallax_with(ParallaxOptions {
+    ///     selector: ".card".to_owned(),
+    ///     max_tilt: 8.0,
+    ///     axis: ParallaxAxis::X,
+    ///     scale_on_hover: 1.03,
+    ///     reset_duration: Some(400.0),
+    ///     glow: None,
+    ///     ..Default::default()
+    /// });
+    /// html!{
+    ///     <div class="card" data-max="12" data-invert="true">{"..."}</div>
+    /// }
+    /// ```
+    #[hook]
+    pub fn use_parallax_with(options: ParallaxOptions) {
+        use_effect_with_deps(
+            move |options| {
+                let parallax = parallax(options.clone());
+                move || drop(parallax)
+            },
+            options,
+        );
+    }
+
+    /// Layers moved on scroll until dropped, see use_scroll_parallax.
+    pub struct ScrollParallax {
+        state: Rc<RefCell<ScrollParallaxState>>,
+        _listeners: Vec<Listener>,
+    }
+
+    struct ScrollParallaxState {
+        selector: String,
+        pending: bool,
+        styles: StyleSnapshot,
+        //offset already applied to every layer, removed when the layer is measured
+        applied: Vec<(HtmlElement, f64)>,
+    }
+
+    impl ScrollParallax {
+        /// Measure and move the layers now, for example after a change of layout.
+        pub fn refresh(&self) {
+            ScrollParallax::update(&self.state);
+        }
+
+        fn request_update(state: &Rc<RefCell<ScrollParallaxState>>) {
+            if std::mem::replace(&mut state.borrow_mut().pending, true) {
+                return;
+            }
+            let weak = Rc::downgrade(state);
+            request_frame(move |_| {
+                if let Some(state) = weak.upgrade() {
+                    ScrollParallax::update(&state);
+                }
+            });
+        }
+
+        fn update(state: &Rc<RefCell<ScrollParallaxState>>) {
+            if reduced_motion() {
+                let mut state = state.borrow_mut();
+                state.styles.restore();
+                state.applied.clear();
+                state.pending = false;
+                return;
+            }
+            let window = crate::window();
+            let selector = state.borrow().selector.clone();
+            let layers = crate::document().query_selector_list(&selector);
+            let scroll_y = window.get_scroll_y();
+            let viewport_center = scroll_y + window.get_height() / 2.0;
+            //read everything first, then write, so the layout is computed once
+            let mut offsets = Vec::new();
+            for i in 0..layers.length() {
+                let layer = layers.get_html(i);
+                let speed = layer
+                    .get_attribute("data-speed")
+                    .and_then(|v| v.trim().parse::<f64>().ok())
+                    .unwrap_or(0.5);
+                let applied = state
+                    .borrow()
+                    .applied
+                    .iter()
+                    .find(|(e, _)| *e == layer)
+                    .map(|(_, offset)| *offset)
+                    .unwrap_or(0.0);
+                let rect = layer.get_bounding_client_rect();
+                let center = rect.top() - applied + scroll_y + rect.height() / 2.0;
+                offsets.push((layer, (viewport_center - center) * speed));
+            }
+            let mut state = state.borrow_mut();
+            for (layer, offset) in &offsets {
+                state.styles.save(layer);
+                layer.set_prop("transform", &format!("translate3d(0, {offset:.2}px, 0)"));
+            }
+            state.applied = offsets;
+            state.pending = false;
+        }
+    }
+
+    impl Drop for ScrollParallax {
+        fn drop(&mut self) {
+            self.state.borrow_mut().styles.restore();
+        }
+    }
+
+    /// Move every element matching selector on scroll, without Yew, the layers are restored when dropped.
+    /// data-speed is the speed of the layer: 0 moves with the page, 0.5 (default) moves at half speed, negative values move faster.
+    pub fn scroll_parallax(selector: &str) -> ScrollParallax {
+        let state = Rc::new(RefCell::new(ScrollParallaxState {
+            selector: selector.to_owned(),
+            pending: false,
+            styles: StyleSnapshot::default(),
+            applied: Vec::new(),
+        }));
+        ScrollParallax::update(&state);
+        let window = crate::window();
+        let listeners = ["scroll", "resize"]
+            .iter()
+            .map(|event| {
+                let weak = Rc::downgrade(&state);
+                listen_passive(&window, event, move |_| {
+                    if let Some(state) = weak.upgrade() {
+                        ScrollParallax::request_update(&state);
+                    }
+                })
+            })
+            .collect();
+        ScrollParallax {
+            state,
+            _listeners: listeners,
+        }
+    }
+
+    /// Move layers at different speeds on scroll.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use_scroll_parallax();
+    /// html!{
+    ///     <section class="hero">
+    ///         <img class="parallax-layer" data-speed="0.8" src="sky.png" />
+    ///         <img class="parallax-layer" data-speed="0.3" src="mountains.png" />
+    ///         <h1 class="parallax-layer" data-speed="-0.2">{"Title"}</h1>
+    ///     </section>
+    /// }
+    /// ```
+    #[hook]
+    pub fn use_scroll_parallax() {
+        use_scroll_parallax_with(".parallax-layer");
+    }
+
+    /// Same as use_scroll_parallax for the elements of selector, the layers are found again on every scroll.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use_scroll_parallax_with(".hero img");
+    /// ```
+    #[hook]
+    pub fn use_scroll_parallax_with(selector: &str) {
+        use_effect_with_deps(
+            move |selector: &String| {
+                let parallax = scroll_parallax(selector);
+                move || drop(parallax)
+            },
+            selector.to_owned(),
+        );
+    }
+
+    /// Elements tilted with the orientation of the device until dropped, see use_orientation_parallax.
+    pub struct OrientationParallax {
+        state: Rc<RefCell<OrientationState>>,
+        _listener: Listener,
+    }
+
+    impl Drop for OrientationParallax {
+        fn drop(&mut self) {
+            self.state.borrow_mut().styles.restore();
+        }
+    }
+
+    #[derive(Default)]
+    struct OrientationState {
+        beta: f64,
+        gamma: f64,
+        //the first beta is how the user holds the device, the tilt is relative to it
+        base_beta: Option<f64>,
+        pending: bool,
+        styles: StyleSnapshot,
+    }
+
+    /// Tilt every element of options.selector with the orientation of the device, without Yew.
+    /// The elements are restored when dropped.
+    pub fn orientation_parallax(options: ParallaxOptions) -> OrientationParallax {
+        let options = Rc::new(options);
+        let state = Rc::new(RefCell::new(OrientationState::default()));
+        let state_event = state.clone();
+        let listener = listen(&crate::window(), "deviceorientation", move |e| {
+            let state = &state_event;
+            if reduced_motion() {
+                state.borrow_mut().styles.restore();
+                return;
+            }
+            let Some(e) = e.dyn_ref::<web_sys::DeviceOrientationEvent>() else { return };
+            let (Some(beta), Some(gamma)) = (e.beta(), e.gamma()) else { return };
+            let request = {
+                let mut s = state.borrow_mut();
+                s.beta = beta;
+                s.gamma = gamma;
+                s.base_beta.get_or_insert(beta);
+                !std::mem::replace(&mut s.pending, true)
+            };
+            if request {
+                let state = Rc::downgrade(state);
+                let options = options.clone();
+                request_frame(move |_| {
+                    let Some(state) = state.upgrade() else { return };
+                    let (x, y) = {
+                        let mut s = state.borrow_mut();
+                        s.pending = false;
+                        let x = (s.gamma / 45.0).clamp(-1.0, 1.0);
+                        let y = ((s.beta - s.base_beta.unwrap_or(s.beta)) / 45.0).clamp(-1.0, 1.0);
+                        (x, y)
+                    };
+                    let all_element = crate::document().query_selector_list(&options.selector);
+                    for i in 0..all_element.length() {
+                        let element = all_element.get_html(i);
+                        let options = options.for_element(&element);
+                        let (rotate_x, rotate_y) = options.tilt(x, y, 2.0, 2.0);
+                        state.borrow_mut().styles.save(&element);
+                        element.set_prop("transform", &options.transform((rotate_x, rotate_y, 1.0)));
+                    }
+                });
+            }
+        });
+        OrientationParallax {
+            state,
+            _listener: listener,
+        }
+    }
+
+    /// Ask the permission for the orientation of the device, needed on iOS.
+    /// It must be called from a user gesture (click, touch...), resolves to true if granted.
+    pub async fn request_orientation_permission() -> bool {
+        let class = js_sys::Reflect::get(&crate::window(), &"DeviceOrientationEvent".into()).unwrap_or(JsValue::UNDEFINED);
+        let request = js_sys::Reflect::get(&class, &"requestPermission".into()).unwrap_or(JsValue::UNDEFINED);
+        let Some(request) = request.dyn_ref::<js_sys::Function>() else {
+            //no permission needed on this browser
+            return !class.is_undefined();
+        };
+        let Ok(promise) = request.call0(&class) else { return false };
+        match wasm_bindgen_futures::JsFuture::from(js_sys::Promise::from(promise)).await {
+            Ok(state) => state.as_string().as_deref() == Some("granted"),
+            Err(_) => false,
+        }
+    }
+
+    /// Same as use_orientation_parallax_with(ParallaxOptions::default()).
+    #[hook]
+    pub fn use_orientation_parallax() {
+        use_orientation_parallax_with(ParallaxOptions::default());
+    }
+
+    /// Tilt elements with the orientation of the device, for mobile where there's not a mouse.
+    /// The data-* attributes of ParallaxOptions are honored, on iOS call request_orientation_permission first.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use_parallax();
+    /// use_orientation_parallax();
+    /// html!{
+    ///     <div class="parallax-effect" data-max="10">{"..."}</div>
+    /// }
+    /// ```
+    #[hook]
+    pub fn use_orientation_parallax_with(options: ParallaxOptions) {
+        use_effect_with_deps(
+            move |options| {
+                let parallax = orientation_parallax(options.clone());
+                move || drop(parallax)
+            },
+            options,
+        );
+    }
+
+    /// Default style of use_ripple and use_custom_cursor, your css always wins on it.
+    pub const INTERACTION_CSS: &str = "\
+:where(.ripple){position:relative;overflow:hidden;}\
+:where(.ripple-ink){position:absolute;border-radius:50%;pointer-events:none;background:currentColor;opacity:.25;transform:scale(0);animation:minimal-ripple var(--ripple-duration,600ms) ease-out forwards;}\
+@keyframes minimal-ripple{to{transform:scale(1);opacity:0;}}\
+:where(.custom-cursor){position:fixed;left:0;top:0;width:24px;height:24px;margin:-12px 0 0 -12px;border:2px solid currentColor;border-radius:50%;pointer-events:none;z-index:9999;opacity:0;transition:opacity .2s ease,width .2s ease,height .2s ease,margin .2s ease;}\
+:where(.custom-cursor.visible){opacity:1;}\
+:where(.custom-cursor.hover){width:48px;height:48px;margin:-24px 0 0 -24px;}\
+.custom-cursor-hidden,.custom-cursor-hidden *{cursor:none;}";
+
+    fn closest_target(e: &web_sys::Event, selector: &str) -> Option<Element> {
+        e.target()
+            .and_then(|t| t.dyn_into::<Element>().ok())
+            .and_then(|t| t.closest(selector).ok().flatten())
+    }
+
+    /// Ripples on the elements with class ripple until dropped, see use_ripple.
+    pub struct Ripple {
+        _listeners: Vec<Listener>,
+        //inks created by this Ripple, other instances keep theirs
+        inks: Rc<RefCell<Vec<Element>>>,
+    }
+
+    impl Drop for Ripple {
+        fn drop(&mut self) {
+            for ink in self.inks.borrow_mut().drain(..) {
+                ink.remove();
+            }
+        }
+    }
+
+    /// Start the ripples without Yew, it lives until the returned Ripple is dropped.
+    pub fn ripple() -> Ripple {
+        crate::style::inject_css("minimal-interaction-style", INTERACTION_CSS);
+        let document = crate::document();
+        let inks: Rc<RefCell<Vec<Element>>> = Rc::new(RefCell::new(Vec::new()));
+        let created = inks.clone();
+        //delegated on document, so elements added later work too
+        let on_down = listen_passive(&document, "pointerdown", move |e| {
+            if reduced_motion() {
+                return;
+            }
+            let Some(host) = closest_target(&e, ".ripple") else { return };
+            let Some(e) = e.dyn_ref::<web_sys::MouseEvent>() else { return };
+            if host.has_attribute("disabled") {
+                return;
+            }
+            let rect = host.get_bounding_client_rect();
+            let x = e.client_x() as f64 - rect.left();
+            let y = e.client_y() as f64 - rect.top();
+            //big enough to cover the farthest corner
+            let radius = x.max(rect.width() - x).hypot(y.max(rect.height() - y));
+            let ink = crate::document().create_html("span");
+            ink.add_class("ripple-ink");
+            ink.set_prop("left", &format!("{:.1}px", x - radius));
+            ink.set_prop("top", &format!("{:.1}px", y - radius));
+            ink.set_prop("width", &format!("{:.1}px", radius * 2.0));
+            ink.set_prop("height", &format!("{:.1}px", radius * 2.0));
+            if let Some(color) = host.get_attribute("data-ripple-color") {
+                ink.set_prop("background", &color);
+            }
+            if let Some(duration) = data_f64(&host, "data-ripple-duration") {
+                ink.set_prop("--ripple-duration", &format!("{duration}ms"));
+            }
+            created.borrow_mut().push(ink.clone().into());
+            host.app_child(ink.into());
+        });
+        let finished = inks.clone();
+        let on_end = listen(&document, "animationend", move |e| {
+            let Some(target) = e.target().and_then(|t| t.dyn_into::<Element>().ok()) else { return };
+            let mut inks = finished.borrow_mut();
+            if let Some(i) = inks.iter().position(|ink| *ink == target) {
+                inks.swap_remove(i).remove();
+            }
+        });
+        Ripple {
+            _listeners: vec![on_down, on_end],
+            inks,
+        }
+    }
+
+    /// Material ink ripple from the pointer on every element with class ripple.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use_ripple();
+    /// html!{
+    ///     <button class="ripple" data-ripple-color="rgba(255,255,255,.4)" data-ripple-duration="800">{"Save"}</button>
+    ///     // data-ripple-color: default currentColor, data-ripple-duration: default 600 ms
+    /// }
+    /// ```
+    #[hook]
+    pub fn use_ripple() {
+        use_effect_with_deps(
+            move |_| {
+                let ripple = ripple();
+                move || drop(ripple)
+            },
+            (),
+        );
+    }
+
+    struct MagneticState {
+        elements: Vec<(HtmlElement, SpringAnimation<(f64, f64)>)>,
+        pointer: Option<(f64, f64)>,
+        pending: bool,
+        styles: StyleSnapshot,
+    }
+
+    /// Elements with class magnetic attracted by the cursor until dropped, see use_magnetic.
+    pub struct Magnetic {
+        state: Rc<RefCell<MagneticState>>,
+        _listeners: Vec<Listener>,
+    }
+
+    impl Magnetic {
+        fn request_update(state: &Rc<RefCell<MagneticState>>) {
+            if std::mem::replace(&mut state.borrow_mut().pending, true) {
+                return;
+            }
+            let weak = Rc::downgrade(state);
+            request_frame(move |_| {
+                if let Some(state) = weak.upgrade() {
+                    Magnetic::update(&state);
+                }
+            });
+        }
+
+        fn update(state: &Rc<RefCell<MagneticState>>) {
+            let mut state = state.borrow_mut();
+            state.pending = false;
+            state.elements.retain(|(element, _)| element.is_connected());
+            let pointer = if reduced_motion() { None } else { state.pointer };
+            let all_element = crate::document().query_selector_list(".magnetic");
+            for i in 0..all_element.length() {
+                let element = all_element.get_html(i);
+                let radius = data_f64(&element, "data-radius").unwrap_or(60.0);
+                let strength = data_f64(&element, "data-strength").unwrap_or(0.3);
+                let index = state.elements.iter().position(|(e, _)| *e == element);
+                let offset = index.map(|i| state.elements[i].1.value()).unwrap_or((0.0, 0.0));
+                //measured without the current offset, so the element doesn't run away from the cursor
+                let rect = element.get_bounding_client_rect();
+                let (width, height) = (rect.width(), rect.height());
+                let center_x = rect.left() + width / 2.0 - offset.0;
+                let center_y = rect.top() + height / 2.0 - offset.1;
+                let target = match pointer {
+                    Some((x, y)) => {
+                        let (dx, dy) = (x - center_x, y - center_y);
+                        let outside = ((dx.abs() - width / 2.0).max(0.0)).hypot((dy.abs() - height / 2.0).max(0.0));
+                        if outside <= radius { (dx * strength, dy * strength) } else { (0.0, 0.0) }
+                    }
+                    None => (0.0, 0.0),
+                };
+                match index {
+                    Some(i) if state.elements[i].1.target() != target => state.elements[i].1.set_target(target),
+                    Some(_) => (),
+                    None if target != (0.0, 0.0) => {
+                        state.styles.save(&element);
+                        let html = element.clone();
+                        let spring = SpringAnimation::new(Spring::GENTLE, (0.0, 0.0), move |(x, y): (f64, f64)| {
+                            html.set_prop("transform", &format!("translate3d({x:.2}px, {y:.2}px, 0)"));
+                        });
+                        spring.set_target(target);
+                        state.elements.push((element, spring));
+                    }
+                    None => (),
+                }
+            }
+        }
+    }
+
+    impl Drop for Magnetic {
+        fn drop(&mut self) {
+            let mut state = self.state.borrow_mut();
+            for (_, spring) in state.elements.drain(..) {
+                spring.stop();
+            }
+            state.styles.restore();
+        }
+    }
+
+    /// Start the magnetic elements without Yew, it lives until the returned Magnetic is dropped.
+    pub fn magnetic() -> Magnetic {
+        let state = Rc::new(RefCell::new(MagneticState {
+            elements: Vec::new(),
+            pointer: None,
+            pending: false,
+            styles: StyleSnapshot::default(),
+        }));
+        let document = crate::document();
+        let state_move = Rc::downgrade(&state);
+        let on_move = listen_passive(&document, "mousemove", move |e| {
+            let (Some(state), Some(e)) = (state_move.upgrade(), e.dyn_ref::<web_sys::MouseEvent>()) else { return };
+            state.borrow_mut().pointer = Some((e.client_x() as f64, e.client_y() as f64));
+            Magnetic::request_update(&state);
+        });
+        let state_out = Rc::downgrade(&state);
+        let on_out = listen_passive(&document, "mouseout", move |e| {
+            let (Some(state), Some(e)) = (state_out.upgrade(), e.dyn_ref::<web_sys::MouseEvent>()) else { return };
+            //the cursor left the window
+            if e.related_target().is_none() {
+                state.borrow_mut().pointer = None;
+                Magnetic::request_update(&state);
+            }
+        });
+        Magnetic {
+            state,
+            _listeners: vec![on_move, on_out],
+        }
+    }
+
+    /// Move every element with class magnetic toward the cursor when it's near.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use_magnetic();
+    /// html!{
+    ///     <a class="magnetic" data-radius="80" data-strength="0.4">{"Contact us"}</a>
+    ///     // data-radius: distance in px from the element where it starts (default 60)
+    ///     // data-strength: fraction of the distance to the cursor (default 0.3)
+    /// }
+    /// ```
+    #[hook]
+    pub fn use_magnetic() {
+        use_effect_with_deps(
+            move |_| {
+                let magnetic = magnetic();
+                move || drop(magnetic)
+            },
+            (),
+        );
+    }
+
+    struct CursorState {
+        cursor: HtmlElement,
+        created: bool,
+        position: Option<(f64, f64)>,
+        target: (f64, f64),
+        frame: Option<FrameLoop>,
+    }
+
+    /// A custom cursor that follows the mouse until dropped, see use_custom_cursor.
+    pub struct CustomCursor {
+        state: Option<Rc<RefCell<CursorState>>>,
+        _listeners: Vec<Listener>,
+    }
+
+    impl CustomCursor {
+        fn start(state: &Rc<RefCell<CursorState>>) {
+            if state.borrow().frame.as_ref().map(|f| f.is_running()).unwrap_or(false) {
+                return;
+            }
+            let weak = Rc::downgrade(state);
+            let frame = frame_loop(move |_| {
+                let Some(state) = weak.upgrade() else { return false };
+                let mut state = state.borrow_mut();
+                let smoothing = match reduced_motion() {
+                    true => 1.0,
+                    false => data_f64(&state.cursor, "data-smoothing").unwrap_or(0.2).clamp(0.01, 1.0),
+                };
+                let target = state.target;
+                let (x, y) = match state.position {
+                    Some((x, y)) => (x + (target.0 - x) * smoothing, y + (target.1 - y) * smoothing),
+                    None => target,
+                };
+                state.position = Some((x, y));
+                state.cursor.set_prop("transform", &format!("translate3d({x:.1}px, {y:.1}px, 0)"));
+                (target.0 - x).hypot(target.1 - y) > 0.1
+            });
+            state.borrow_mut().frame = Some(frame);
+        }
+    }
+
+    impl Drop for CustomCursor {
+        fn drop(&mut self) {
+            let Some(state) = &self.state else { return };
+            let mut state = state.borrow_mut();
+            state.frame = None;
+            if state.created {
+                state.cursor.remove();
+            } else {
+                state.cursor.remove_class("visible");
+                state.cursor.remove_class("hover");
+            }
+            crate::window().document_element_html().remove_class("custom-cursor-hidden");
+        }
+    }
+
+    /// Start the custom cursor without Yew, it lives until the returned CustomCursor is dropped.
+    /// It uses the element with class custom-cursor, or creates one, and does nothing without a mouse.
+    pub fn custom_cursor() -> CustomCursor {
+        crate::style::inject_css("minimal-interaction-style", INTERACTION_CSS);
+        if !crate::media::media_query("(pointer: fine)").matches() {
+            return CustomCursor {
+                state: None,
+                _listeners: Vec::new(),
+            };
+        }
+        let document = crate::document();
+        let (cursor, created) = match document.query_selector(".custom-cursor").ok().flatten() {
+            Some(cursor) => (cursor.to_html(), false),
+            None => {
+                let cursor = document.create_html("div");
+                cursor.add_class("custom-cursor");
+                cursor.set_attr("aria-hidden", "true");
+                document.body().custom_expect("There is no body".to_owned()).app_child(cursor.clone().into());
+                (cursor, true)
+            }
+        };
+        if cursor.get_attribute("data-hide-native").map(|v| v.trim() == "true").unwrap_or(false) {
+            crate::window().document_element_html().add_class("custom-cursor-hidden");
+        }
+        let state = Rc::new(RefCell::new(CursorState {
+            cursor,
+            created,
+            position: None,
+            target: (0.0, 0.0),
+            frame: None,
+        }));
+        let state_move = Rc::downgrade(&state);
+        let on_move = listen_passive(&document, "mousemove", move |e| {
+            let Some(state) = state_move.upgrade() else { return };
+            let hover = closest_target(&e, "a, button, [role=button], .magnetic, [data-cursor-hover]").is_some();
+            let Some(e) = e.dyn_ref::<web_sys::MouseEvent>() else { return };
+            {
+                let mut state = state.borrow_mut();
+                state.target = (e.client_x() as f64, e.client_y() as f64);
+                state.cursor.add_class("visible");
+                match hover {
+                    true => state.cursor.add_class("hover"),
+                    false => state.cursor.remove_class("hover"),
+                }
+            }
+            CustomCursor::start(&state);
+        });
+        let state_out = Rc::downgrade(&state);
+        let on_out = listen_passive(&document, "mouseout", move |e| {
+            let (Some(state), Some(e)) = (state_out.upgrade(), e.dyn_ref::<web_sys::MouseEvent>()) else { return };
+            if e.related_target().is_none() {
+                let mut state = state.borrow_mut();
+                state.cursor.remove_class("visible");
+                //it jumps to the cursor when it's back
+                state.position = None;
+            }
+        });
+        CustomCursor {
+            state: Some(state),
+            _listeners: vec![on_move, on_out],
+        }
+    }
+
+    /// Show an element that follows the mouse with smoothing, it grows over links and buttons.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use_custom_cursor();
+    /// html!{
+    ///     <div class="custom-cursor" data-smoothing="0.15" data-hide-native="true"></div>
+    ///     // optional, a div.custom-cursor is created if there isn't one
+    ///     // data-smoothing: from 0.01 (slow) to 1 (no delay), default 0.2
+    ///     // data-hide-native: hide the cursor of the system, default false
+    ///     // the class hover is added over links, buttons, .magnetic and [data-cursor-hover]
+    /// }
+    /// ```
+    #[hook]
+    pub fn use_custom_cursor() {
+        use_effect_with_deps(
+            move |_| {
+                let cursor = custom_cursor();
+                move || drop(cursor)
+            },
+            (),
+        );
+    }
+
+    /// Default style of the reveal animations of use_scroll_reveal, your css always wins on it.
+    pub const REVEAL_CSS: &str = "\
+:where(.reveal){opacity:0;transition:opacity .6s ease,transform .6s ease;}\
+:where(.reveal:not([data-reveal]),.reveal[data-reveal=fade-up]){transform:translateY(24px);}\
+:where(.reveal[data-reveal=fade-down]){transform:translateY(-24px);}\
+:where(.reveal[data-reveal=fade-left]){transform:translateX(24px);}\
+:where(.reveal[data-reveal=fade-right]){transform:translateX(-24px);}\
+:where(.reveal[data-reveal=zoom-in]){transform:scale(.9);}\
+:where(.reveal[data-reveal=zoom-out]){transform:scale(1.1);}\
+.reveal.revealed{opacity:1;transform:none;}\
+@media (prefers-reduced-motion: reduce){:root:not([data-motion=full]) .reveal{opacity:1;transform:none;transition:none;}}\
+:root[data-motion=reduced] .reveal{opacity:1;transform:none;transition:none;}";
+
+    /// Elements with class reveal observed until dropped, see use_scroll_reveal.
+    pub struct ScrollReveal {
+        observer: Option<web_sys::IntersectionObserver>,
+        mutations: web_sys::MutationObserver,
+        _callback: Option<Closure<dyn FnMut(js_sys::Array, web_sys::IntersectionObserver)>>,
+        _on_mutation: Closure<dyn FnMut(js_sys::Array, web_sys::MutationObserver)>,
+    }
+
+    impl Drop for ScrollReveal {
+        fn drop(&mut self) {
+            self.mutations.disconnect();
+            if let Some(observer) = &self.observer {
+                observer.disconnect();
+            }
+        }
+    }
+
+    /// Reveal every element with class reveal when it enters the viewport, without Yew.
+    /// Elements added to the page later are observed too.
+    pub fn scroll_reveal() -> ScrollReveal {
+        crate::style::inject_css("minimal-reveal-style", REVEAL_CSS);
+        let (observer, callback) = if reduced_motion() {
+            (None, None)
+        } else {
+            let callback = Closure::<dyn FnMut(js_sys::Array, web_sys::IntersectionObserver)>::new(
+                move |entries: js_sys::Array, observer: web_sys::IntersectionObserver| {
+                    for entry in entries.iter() {
+                        let entry = entry.unchecked_into::<web_sys::IntersectionObserverEntry>();
+                        let element = entry.target();
+                        let once = element
+                            .get_attribute("data-once")
+                            .map(|v| v.trim() != "false")
+                            .unwrap_or(false);
+                        if entry.is_intersecting() {
+                            if let Some(delay) = element.get_attribute("data-delay") {
+                                if let Ok(delay) = delay.trim().parse::<f64>() {
+                                    element.to_html().set_prop("transition-delay", &format!("{delay}ms"));
+                                }
+                            }
+                            element.add_class("revealed");
+                            if once {
+                                observer.unobserve(&element);
+                            }
+                        } else if !once {
+                            element.remove_class("revealed");
+                        }
+                    }
+                },
+            );
+            let mut options = web_sys::IntersectionObserverInit::new();
+            options.threshold(&JsValue::from(0.15)).root_margin("0px 0px -10% 0px");
+            let observer =
+                web_sys::IntersectionObserver::new_with_options(callback.as_ref().unchecked_ref(), &options)
+                    .custom_expect("It's not possible create an IntersectionObserver".to_owned());
+            (Some(observer), Some(callback))
+        };
+        //observing an element twice is a no-op, so every new batch just observes what isn't revealed yet
+        let track = {
+            let observer = observer.clone();
+            move || {
+                let all_element = crate::document().query_selector_list(".reveal:not(.revealed)");
+                match &observer {
+                    Some(observer) => {
+                        for i in 0..all_element.length() {
+                            observer.observe(&all_element.get_el(i));
+                        }
+                    }
+                    None => all_element.add_list_class("revealed"),
+                }
+            }
+        };
+        track();
+        let on_mutation = Closure::<dyn FnMut(js_sys::Array, web_sys::MutationObserver)>::new(
+            move |_: js_sys::Array, _: web_sys::MutationObserver| track(),
+        );
+        let mutations = web_sys::MutationObserver::new(on_mutation.as_ref().unchecked_ref())
+            .custom_expect("It's not possible create a MutationObserver".to_owned());
+        let mut init = web_sys::MutationObserverInit::new();
+        init.child_list(true).subtree(true);
+        mutations
+            .observe_with_options(&crate::document().body().custom_expect("There is no body".to_owned()), &init)
+            .custom_expect("It's not possible observe the body".to_owned());
+        ScrollReveal {
+            observer,
+            mutations,
+            _callback: callback,
+            _on_mutation: on_mutation,
+        }
+    }
+
+    /// Animate elements with class reveal when they enter the viewport.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use minimal_web::animation::use_scroll_reveal;
+    ///
+    /// use_scroll_reveal();
+    /// html!{
+    ///     <div class="reveal" data-reveal="fade-left" data-delay="150" data-once="true">
+    ///     // data-reveal: fade, fade-up (default), fade-down, fade-left, fade-right, zoom-in, zoom-out
+    ///     // data-delay: delay in ms, data-once: reveal only the first time
+    ///     ...
+    ///     </div>
+    /// }
+    /// ```
+    #[hook]
+    pub fn use_scroll_reveal() {
+        let reduced = use_reduced_motion();
+        use_effect_with_deps(
+            move |_| {
+                let reveal = scroll_reveal();
+                move || drop(reveal)
+            },
+            reduced,
+        );
+    }
+
+    /// Default style of the text effects, your css always wins on it.
+    /// The cursor of typewriter can be changed with --typewriter-cursor, typed is added when it ends.
+    pub const TEXT_EFFECTS_CSS: &str = "\
+:where(.typewriter)::after{content:var(--typewriter-cursor,'|');margin-left:.05em;animation:minimal-blink 1s step-end infinite;}\
+:where(.typewriter.typed)::after{animation:none;opacity:0;}\
+:where(.count-up){font-variant-numeric:tabular-nums;}\
+@keyframes minimal-blink{50%{opacity:0;}}\
+@media (prefers-reduced-motion: reduce){:root:not([data-motion=full]) .typewriter::after{animation:none;}}\
+:root[data-motion=reduced] .typewriter::after{animation:none;}";
+
+    const TYPEWRITER_PAUSE: f64 = 1500.0;
+    const SCRAMBLE_CHARS: &str = "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789!<>-_/[]{}=+*^?#";
+
+    fn data_f64(element: &Element, name: &str) -> Option<f64> {
+        element.get_attribute(name).and_then(|v| v.trim().parse::<f64>().ok())
+    }
+
+    enum TypewriterPhase {
+        Typing,
+        Pausing(f64),
+        Deleting,
+    }
+
+    /// A running typewriter, it stops when dropped.
+    pub struct Typewriter {
+        frame: Option<FrameLoop>,
+    }
+
+    impl Typewriter {
+        /// Check if it's still typing.
+        pub fn is_running(&self) -> bool {
+            self.frame.as_ref().map(|f| f.is_running()).unwrap_or(false)
+        }
+
+        /// Stop where it is.
+        pub fn stop(&self) {
+            if let Some(frame) = &self.frame {
+                frame.stop();
+            }
+        }
+    }
+
+    /// Type texts in element one character every speed milliseconds, delete it and type the next one.
+    /// With looped false it stops on the last text, with reduced motion it shows the first text at once.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let title = document().query_selector_html("h1 span");
+    /// let typewriter = typewriter(&title, &["fast", "minimal", "safe"], 80.0, true);
+    /// ```
+    pub fn typewriter<T: AsRef<str>>(element: &HtmlElement, texts: &[T], speed: f64, looped: bool) -> Typewriter {
+        crate::style::inject_css("minimal-text-effects-style", TEXT_EFFECTS_CSS);
+        element.add_class("typewriter");
+        element.remove_class("typed");
+        let texts: Vec<Vec<char>> = texts.iter().map(|t| t.as_ref().chars().collect()).collect();
+        let target = element.clone();
+        let write = move |text: &[char]| {
+            target.set_text_content(Some(&text.iter().collect::<String>()));
+        };
+        if texts.is_empty() || reduced_motion() {
+            write(texts.first().map(|t| t.as_slice()).unwrap_or(&[]));
+            element.add_class("typed");
+            return Typewriter { frame: None };
+        }
+        let element = element.clone();
+        let (mut index, mut shown, mut phase, mut last) = (0, 0, TypewriterPhase::Typing, None::<f64>);
+        let frame = frame_loop(move |timestamp| {
+            let text = &texts[index];
+            let interval = match phase {
+                TypewriterPhase::Deleting => speed / 2.0,
+                _ => speed,
+            };
+            if last.map(|l| timestamp - l < interval).unwrap_or(false) {
+                return true;
+            }
+            last = Some(timestamp);
+            match phase {
+                TypewriterPhase::Typing if shown < text.len() => {
+                    shown += 1;
+                    write(&text[..shown]);
+                }
+                TypewriterPhase::Typing => {
+                    if index + 1 == texts.len() && !looped {
+                        element.add_class("typed");
+                        return false;
+                    }
+                    phase = TypewriterPhase::Pausing(timestamp);
+                }
+                TypewriterPhase::Pausing(start) => {
+                    if timestamp - start >= TYPEWRITER_PAUSE {
+                        phase = TypewriterPhase::Deleting;
+                    }
+                }
+                TypewriterPhase::Deleting if shown > 0 => {
+                    shown -= 1;
+                    write(&text[..shown]);
+                }
+                TypewriterPhase::Deleting => {
+                    index = (index + 1) % texts.len();
+                    phase = TypewriterPhase::Typing;
+                }
+            }
+            true
+        });
+        Typewriter { frame: Some(frame) }
+    }
+
+    /// Same as scramble with the duration in milliseconds and the random characters.
+    pub fn scramble_with(element: &HtmlElement, final_text: &str, duration: f64, chars: &str) -> Animation {
+        let element = element.clone();
+        let text: Vec<char> = final_text.chars().collect();
+        let chars: Vec<char> = match chars.is_empty() {
+            true => SCRAMBLE_CHARS.chars().collect(),
+            false => chars.chars().collect(),
+        };
+        let options = AnimationOptions {
+            duration,
+            easing: Easing::Linear,
+            ..Default::default()
+        };
+        animate(0.0, 1.0, options, move |t: f64| {
+            let revealed = (t * text.len() as f64).floor() as usize;
+            let shown: String = text
+                .iter()
+                .enumerate()
+                .map(|(i, c)| match i < revealed || c.is_whitespace() {
+                    true => *c,
+                    false => chars[(js_sys::Math::random() * chars.len() as f64) as usize % chars.len()],
+                })
+                .collect();
+            element.set_text_content(Some(&shown));
+        })
+    }
+
+    /// Decode final_text in element from random characters, from left to right in 800 ms.
+    /// It stops when the returned Animation is dropped.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let title = document().query_selector_html("h1");
+    /// let decode = scramble(&title, "Hello world");
+    /// ```
+    pub fn scramble(element: &HtmlElement, final_text: &str) -> Animation {
+        scramble_with(element, final_text, 800.0, SCRAMBLE_CHARS)
+    }
+
+    #[wasm_bindgen]
+    extern {
+        //Intl.NumberFormat throws a RangeError for a bad locale or too many decimals
+        #[wasm_bindgen(catch, js_namespace = Intl, js_name = NumberFormat)]
+        fn number_format(locales: &js_sys::Array, options: &js_sys::Object) -> Result<js_sys::Intl::NumberFormat, JsValue>;
+    }
+
+    /// Format a number with the locale of the browser (or locale, for example "de-DE") and a fixed number of decimals.
+    /// decimals is capped at 20 and an invalid locale is replaced by the locale of the browser.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// assert_eq!(format_number(12345.678, 1, Some("en-US")), "12,345.7");
+    /// ```
+    pub fn format_number(value: f64, decimals: u32, locale: Option<&str>) -> String {
+        let decimals = decimals.min(20);
+        let locales = js_sys::Array::new();
+        if let Some(locale) = locale {
+            locales.push(&locale.into());
+        }
+        let options = js_sys::Object::new();
+        for key in ["minimumFractionDigits", "maximumFractionDigits"] {
+            let _ = js_sys::Reflect::set(&options, &key.into(), &decimals.into());
+        }
+        number_format(&locales, &options)
+            .or_else(|_| number_format(&js_sys::Array::new(), &options))
+            .ok()
+            .and_then(|formatter| formatter.format().call1(&JsValue::NULL, &value.into()).ok())
+            .and_then(|v| v.as_string())
+            .unwrap_or_else(|| format!("{value:.0$}", decimals as usize))
+    }
+
+    /// Count from a number to another in duration milliseconds, format converts the number to text.
+    /// It stops when the returned Animation is dropped.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let users = document().query_selector_html(".users");
+    /// let counter = count_up(&users, 0.0, 12500.0, 2000.0, |v| format!("{}+", format_number(v, 0, None)));
+    /// ```
+    pub fn count_up<F>(element: &HtmlElement, from: f64, to: f64, duration: f64, format: F) -> Animation
+    where
+        F: Fn(f64) -> String + 'static,
+    {
+        crate::style::inject_css("minimal-text-effects-style", TEXT_EFFECTS_CSS);
+        let element = element.clone();
+        let options = AnimationOptions {
+            duration,
+            easing: Easing::OutCubic,
+            ..Default::default()
+        };
+        animate(from, to, options, move |v: f64| element.set_text_content(Some(&format(v))))
+    }
+
+    /// Start a typewriter on every element with class typewriter.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use_typewriter();
+    /// html!{
+    ///     <span class="typewriter" data-texts="fast|minimal|safe" data-speed="80" data-loop="true"></span>
+    ///     // data-texts: texts separated by |, data-speed: ms per character (default 80), data-loop: default true
+    /// }
+    /// ```
+    #[hook]
+    pub fn use_typewriter() {
+        use_effect_with_deps(
+            move |_| {
+                let all_element = crate::document().query_selector_list(".typewriter");
+                let running: Vec<Typewriter> = (0..all_element.length())
+                    .map(|i| {
+                        let element = all_element.get_html(i);
+                        let texts = element.get_attribute("data-texts").unwrap_or_else(|| element.get_text_content());
+                        let texts: Vec<&str> = texts.split('|').map(|t| t.trim()).collect();
+                        let speed = data_f64(&element, "data-speed").unwrap_or(80.0);
+                        let looped = element.get_attribute("data-loop").map(|v| v.trim() != "false").unwrap_or(true);
+                        typewriter(&element, &texts, speed, looped)
+                    })
+                    .collect();
+                move || drop(running)
+            },
+            (),
+        );
+    }
+
+    /// Decode the text of every element with class scramble.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use_scramble();
+    /// html!{
+    ///     <h1 class="scramble" data-text="Hello world" data-duration="1200">{"Hello world"}</h1>
+    ///     // data-text: final text (default the text of the element), data-duration: default 800, data-chars: random characters
+    /// }
+    /// ```
+    #[hook]
+    pub fn use_scramble() {
+        use_effect_with_deps(
+            move |_| {
+                let all_element = crate::document().query_selector_list(".scramble");
+                let running: Vec<Animation> = (0..all_element.length())
+                    .map(|i| {
+                        let element = all_element.get_html(i);
+                        let text = element.get_attribute("data-text").unwrap_or_else(|| element.get_text_content());
+                        let duration = data_f64(&element, "data-duration").unwrap_or(800.0);
+                        let chars = element.get_attribute("data-chars").unwrap_or_default();
+                        scramble_with(&element, &text, duration, &chars)
+                    })
+                    .collect();
+                move || drop(running)
+            },
+            (),
+        );
+    }
+
+    /// Count up the number of every element with class count-up.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use_count_up();
+    /// html!{
+    ///     <span class="count-up" data-to="12500" data-suffix="+" data-locale="it-IT">{"12500"}</span>
+    ///     // data-from: default 0, data-to: default the number in the element, data-duration: default 2000
+    ///     // data-decimals: default 0, data-locale: default the locale of the browser, data-prefix and data-suffix
+    /// }
+    /// ```
+    #[hook]
+    pub fn use_count_up() {
+        use_effect_with_deps(
+            move |_| {
+                let all_element = crate::document().query_selector_list(".count-up");
+                let running: Vec<Animation> = (0..all_element.length())
+                    .filter_map(|i| {
+                        let element = all_element.get_html(i);
+                        let to = data_f64(&element, "data-to").or_else(|| element.get_text_content().trim().parse().ok())?;
+                        let from = data_f64(&element, "data-from").unwrap_or(0.0);
+                        let duration = data_f64(&element, "data-duration").unwrap_or(2000.0);
+                        let decimals = data_f64(&element, "data-decimals").unwrap_or(0.0).max(0.0) as u32;
+                        let locale = element.get_attribute("data-locale");
+                        let prefix = element.get_attribute("data-prefix").unwrap_or_default();
+                        let suffix = element.get_attribute("data-suffix").unwrap_or_default();
+                        Some(count_up(&element, from, to, duration, move |v| {
+                            format!("{prefix}{}{suffix}", format_number(v, decimals, locale.as_deref()))
+                        }))
+                    })
+                    .collect();
+                move || drop(running)
+            },
+            (),
+        );
+    }
+}
+pub mod carousel {
+    use std::cell::{Cell, RefCell};
+    use std::rc::{Rc, Weak};
+    use wasm_bindgen::prelude::*;
+    use yew::prelude::*;
+    use web_sys::{Element, HtmlElement};
+    use crate::animation::{reduced_motion, Animation, AnimationOptions, Easing};
+    use crate::utils::*;
+
+    /// Default style of the carousel, your css always wins on it.
+    pub const CAROUSEL_CSS: &str = "\
+:where(.carousel){position:relative;}\
+:where(.carousel-track){display:grid;overflow:hidden;touch-action:pan-y;}\
+:where(.carousel-track img){-webkit-user-drag:none;user-select:none;}\
+:where(.carousel-slide){grid-area:1/1;min-width:0;}\
+:where(.carousel-slide:not(.active,.moving)){visibility:hidden;}\
+:where(.carousel-prev,.carousel-next){position:absolute;top:50%;transform:translateY(-50%);z-index:1;}\
+:where(.carousel-prev){left:.5rem;}\
+:where(.carousel-next){right:.5rem;}\
+:where(.carousel-dots){display:flex;justify-content:center;gap:.5rem;margin-top:.75rem;}\
+:where(.carousel-dot){width:.625rem;height:.625rem;padding:0;border:0;border-radius:50%;background:currentColor;opacity:.3;cursor:pointer;}\
+:where(.carousel-dot[aria-current=true]){opacity:1;}";
+
+    thread_local! {
+        static NEXT_ID: Cell<u32> = const { Cell::new(0) };
+    }
+
+    /// How a slide replaces the previous one.
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+    pub enum CarouselTransition {
+        #[default]
+        Slide,
+        Fade,
+    }
+
+    impl CarouselTransition {
+        /// Get a CarouselTransition from "slide" or "fade".
+        pub fn parse(value: &str) -> Option<CarouselTransition> {
+            match value.trim() {
+                "slide" => Some(CarouselTransition::Slide),
+                "fade" => Some(CarouselTransition::Fade),
+                _ => None,
+            }
+        }
+    }
+
+    /// Options of carousel and Carousel.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let options = CarouselOptions {
+    ///     transition: CarouselTransition::Fade,
+    ///     autoplay: Some(5000.0),
+    ///     ..Default::default()
+    /// };
+    /// ```
+    #[derive(Clone, Debug, PartialEq)]
+    pub struct CarouselOptions {
+        pub transition: CarouselTransition,
+        pub duration: f64,
+        pub easing: Easing,
+        /// After the last slide comes the first one.
+        pub looped: bool,
+        /// Milliseconds between slides, None for no autoplay.
+        pub autoplay: Option<f64>,
+        pub pause_on_hover: bool,
+        pub keyboard: bool,
+        pub swipe: bool,
+        pub dots: bool,
+        pub arrows: bool,
+        /// aria-label of the carousel.
+        pub label: String,
+    }
+
+    impl Default for CarouselOptions {
+        fn default() -> Self {
+            CarouselOptions {
+                transition: CarouselTransition::Slide,
+                duration: 500.0,
+                easing: Easing::EASE_IN_OUT,
+                looped: true,
+                autoplay: None,
+                pause_on_hover: true,
+                keyboard: true,
+                swipe: true,
+                dots: true,
+                arrows: true,
+                label: "Carousel".to_owned(),
+            }
+        }
+    }
+
+    fn data_bool(element: &Element, name: &str) -> Option<bool> {
+        element.get_attribute(name).map(|v| v.trim() != "false")
+    }
+
+    impl CarouselOptions {
+        /// Get the options of element, its data-* attributes win on self:
+        /// data-transition, data-duration, data-loop, data-autoplay (ms or false), data-pause-on-hover,
+        /// data-keyboard, data-swipe, data-dots, data-arrows and aria-label.
+        pub fn for_element(&self, element: &Element) -> CarouselOptions {
+            let mut options = self.clone();
+            if let Some(transition) = element.get_attribute("data-transition").and_then(|v| CarouselTransition::parse(&v)) {
+                options.transition = transition;
+            }
+            if let Some(duration) = element.get_attribute("data-duration").and_then(|v| v.trim().parse::<f64>().ok()) {
+                options.duration = duration;
+            }
+            if let Some(autoplay) = element.get_attribute("data-autoplay") {
+                options.autoplay = autoplay.trim().parse::<f64>().ok().filter(|v| *v > 0.0);
+            }
+            for (name, value) in [
+                ("data-loop", &mut options.looped),
+                ("data-pause-on-hover", &mut options.pause_on_hover),
+                ("data-keyboard", &mut options.keyboard),
+                ("data-swipe", &mut options.swipe),
+                ("data-dots", &mut options.dots),
+                ("data-arrows", &mut options.arrows),
+            ] {
+                if let Some(flag) = data_bool(element, name) {
+                    *value = flag;
+                }
+            }
+            if let Some(label) = element.get_attribute("aria-label") {
+                options.label = label;
+            }
+            options
+        }
+    }
+
+    struct Drag {
+        pointer: i32,
+        start: f64,
+        dx: f64,
+        width: f64,
+    }
+
+    struct CarouselState {
+        options: CarouselOptions,
+        root: HtmlElement,
+        track: HtmlElement,
+        slides: Vec<HtmlElement>,
+        dots: Vec<HtmlElement>,
+        created: Vec<HtmlElement>,
+        //attributes before the carousel started, restored on drop
+        saved: Vec<(HtmlElement, &'static str, Option<String>)>,
+        added_class: bool,
+        current: usize,
+        animation: Option<Animation>,
+        drag: Option<Drag>,
+        dragged: bool,
+        hovered: bool,
+        focused: bool,
+        paused: bool,
+        autoplay: Option<Interval>,
+    }
+
+    /// A carousel started by carousel(), it's stopped, its controls removed and its attributes restored when dropped.
+    pub struct CarouselHandle {
+        state: Rc<RefCell<CarouselState>>,
+        _listeners: Vec<Listener>,
+    }
+
+    fn render(outgoing: &HtmlElement, incoming: &HtmlElement, transition: CarouselTransition, direction: f64, progress: f64) {
+        match transition {
+            CarouselTransition::Slide => {
+                outgoing.set_prop("transform", &format!("translateX({:.3}%)", -direction * progress * 100.0));
+                incoming.set_prop("transform", &format!("translateX({:.3}%)", direction * (1.0 - progress) * 100.0));
+            }
+            CarouselTransition::Fade => {
+                outgoing.set_prop("opacity", &format!("{:.3}", 1.0 - progress));
+                incoming.set_prop("opacity", &format!("{:.3}", progress));
+            }
+        }
+    }
+
+    fn clear(element: &HtmlElement) {
+        element.remove_class("moving");
+        element.remove_prop("transform");
+        element.remove_prop("opacity");
+    }
+
+    impl CarouselState {
+        fn neighbor(&self, direction: f64) -> Option<usize> {
+            let count = self.slides.len();
+            if count < 2 {
+                return None;
+            }
+            match direction > 0.0 {
+                true if self.current + 1 < count => Some(self.current + 1),
+                true if self.options.looped => Some(0),
+                false if self.current > 0 => Some(self.current - 1),
+                false if self.options.looped => Some(count - 1),
+                _ => None,
+            }
+        }
+
+        fn update_aria(&self) {
+            let count = self.slides.len();
+            for (i, slide) in self.slides.iter().enumerate() {
+                slide.set_attr("aria-label", &format!("{} of {}", i + 1, count));
+                if i == self.current {
+                    slide.add_class("active");
+                    slide.remove_attr("aria-hidden");
+                    slide.remove_attr("inert");
+                } else {
+                    slide.remove_class("active");
+                    slide.set_attr("aria-hidden", "true");
+                    slide.set_attr("inert", "");
+                }
+            }
+            for (i, dot) in self.dots.iter().enumerate() {
+                match i == self.current {
+                    true => dot.set_attr("aria-current", "true"),
+                    false => dot.remove_attr("aria-current"),
+                }
+            }
+            //announce the new slide only when the user changes it
+            let live = if self.autoplay.is_some() && !self.paused { "off" } else { "polite" };
+            self.track.set_attr("aria-live", live);
+        }
+
+        fn is_waiting(&self) -> bool {
+            self.paused
+                || self.focused
+                || self.drag.is_some()
+                || (self.options.pause_on_hover && self.hovered)
+                || crate::document().hidden()
+        }
+    }
+
+    impl CarouselHandle {
+        /// Go to the next slide.
+        pub fn next(&self) {
+            CarouselHandle::step(&self.state, 1.0);
+        }
+
+        /// Go to the previous slide.
+        pub fn prev(&self) {
+            CarouselHandle::step(&self.state, -1.0);
+        }
+
+        /// Go to a slide, the first is 0.
+        pub fn go_to(&self, index: usize) {
+            CarouselHandle::show(&self.state, index);
+        }
+
+        /// Get the index of the slide shown.
+        pub fn current(&self) -> usize {
+            self.state.borrow().current
+        }
+
+        /// Get the number of slides.
+        pub fn len(&self) -> usize {
+            self.state.borrow().slides.len()
+        }
+
+        /// Check if there are no slides.
+        pub fn is_empty(&self) -> bool {
+            self.len() == 0
+        }
+
+        /// Stop the autoplay until resume() is called.
+        pub fn pause(&self) {
+            self.state.borrow_mut().paused = true;
+            self.state.borrow().update_aria();
+        }
+
+        /// Restart the autoplay.
+        pub fn resume(&self) {
+            self.state.borrow_mut().paused = false;
+            CarouselHandle::restart_autoplay(&self.state);
+        }
+
+        fn step(state: &Rc<RefCell<CarouselState>>, direction: f64) {
+            let target = state.borrow().neighbor(direction);
+            if let Some(target) = target {
+                CarouselHandle::run(state, target, direction, 0.0);
+            }
+        }
+
+        fn show(state: &Rc<RefCell<CarouselState>>, index: usize) {
+            let (current, count) = {
+                let s = state.borrow();
+                (s.current, s.slides.len())
+            };
+            if index < count && index != current {
+                CarouselHandle::run(state, index, if index > current { 1.0 } else { -1.0 }, 0.0);
+            }
+        }
+
+        //move from the current slide to target, from is the progress already done by a swipe
+        fn run(state: &Rc<RefCell<CarouselState>>, target: usize, direction: f64, from: f64) {
+            let running = state.borrow_mut().animation.take();
+            if let Some(running) = running {
+                running.finish();
+            }
+            let (outgoing, incoming, options) = {
+                let mut s = state.borrow_mut();
+                let outgoing = s.slides[s.current].clone();
+                s.current = target;
+                s.update_aria();
+                (outgoing, s.slides[target].clone(), s.options.clone())
+            };
+            CarouselHandle::animate(state, outgoing, incoming, options, direction, from, 1.0);
+            CarouselHandle::restart_autoplay(state);
+        }
+
+        //bring back the current slide after a short swipe
+        fn settle(state: &Rc<RefCell<CarouselState>>, neighbor: usize, direction: f64, from: f64) {
+            let (current, neighbor, options) = {
+                let s = state.borrow();
+                (s.slides[s.current].clone(), s.slides[neighbor].clone(), s.options.clone())
+            };
+            CarouselHandle::animate(state, current, neighbor, options, direction, from, 0.0);
+        }
+
+        fn animate(
+            state: &Rc<RefCell<CarouselState>>,
+            outgoing: HtmlElement,
+            incoming: HtmlElement,
+            options: CarouselOptions,
+            direction: f64,
+            from: f64,
+            to: f64,
+        ) {
+            outgoing.add_class("moving");
+            incoming.add_class("moving");
+            let animation_options = AnimationOptions {
+                duration: options.duration * (to - from).abs(),
+                easing: options.easing,
+                ..Default::default()
+            };
+            let (out_update, in_update) = (outgoing.clone(), incoming.clone());
+            let transition = options.transition;
+            let animation = Animation::with_progress(animation_options, move |t| {
+                render(&out_update, &in_update, transition, direction, from + (to - from) * t);
+            });
+            animation.on_complete(move || {
+                clear(&outgoing);
+                clear(&incoming);
+            });
+            state.borrow_mut().animation = Some(animation.clone());
+            animation.play();
+        }
+
+        fn restart_autoplay(state: &Rc<RefCell<CarouselState>>) {
+            let Some(every) = state.borrow().options.autoplay else { return };
+            let weak = Rc::downgrade(state);
+            let autoplay = interval(every as u32, move || {
+                let Some(state) = weak.upgrade() else { return };
+                if state.borrow().is_waiting() {
+                    return;
+                }
+                //autoplay always starts again from the first slide
+                let target = {
+                    let s = state.borrow();
+                    s.neighbor(1.0).or(if s.slides.len() > 1 { Some(0) } else { None })
+                };
+                if let Some(target) = target {
+                    CarouselHandle::run(&state, target, 1.0, 0.0);
+                }
+            });
+            let mut s = state.borrow_mut();
+            s.autoplay = Some(autoplay);
+            s.update_aria();
+        }
+
+        fn on_pointer(state: &Weak<RefCell<CarouselState>>, e: &web_sys::Event) {
+            let Some(state) = state.upgrade() else { return };
+            let Some(e) = e.dyn_ref::<web_sys::PointerEvent>() else { return };
+            //an empty carousel has no current slide to drag
+            if state.borrow().slides.is_empty() {
+                return;
+            }
+            match e.type_().as_str() {
+                "pointerdown" => {
+                    if e.button() != 0 || state.borrow().animation.as_ref().map(|a| a.is_running()).unwrap_or(false) {
+                        return;
+                    }
+                    let mut s = state.borrow_mut();
+                    let width = s.track.offset_width() as f64;
+                    s.drag = Some(Drag {
+                        pointer: e.pointer_id(),
+                        start: e.client_x() as f64,
+                        dx: 0.0,
+                        width: width.max(1.0),
+                    });
+                }
+                "pointermove" => {
+                    let mut s = state.borrow_mut();
+                    let Some(drag) = s.drag.as_mut() else { return };
+                    if drag.pointer != e.pointer_id() {
+                        return;
+                    }
+                    if drag.dx == 0.0 {
+                        let _ = s.track.set_pointer_capture(e.pointer_id());
+                    }
+                    let drag = s.drag.as_mut().custom_expect("The drag of the carousel is missing".to_owned());
+                    drag.dx = e.client_x() as f64 - drag.start;
+                    let (dx, width) = (drag.dx, drag.width);
+                    let direction = if dx < 0.0 { 1.0 } else { -1.0 };
+                    let current = s.slides[s.current].clone();
+                    match s.neighbor(direction) {
+                        Some(neighbor) if s.options.transition == CarouselTransition::Slide && !reduced_motion() => {
+                            for (i, slide) in s.slides.iter().enumerate() {
+                                if i != neighbor && i != s.current {
+                                    clear(slide);
+                                }
+                            }
+                            let neighbor = s.slides[neighbor].clone();
+                            current.add_class("moving");
+                            neighbor.add_class("moving");
+                            render(&current, &neighbor, CarouselTransition::Slide, direction, (dx.abs() / width).min(1.0));
+                        }
+                        //resistance on the edges
+                        None if s.options.transition == CarouselTransition::Slide && !reduced_motion() => {
+                            current.set_prop("transform", &format!("translateX({:.2}px)", dx / 3.0));
+                        }
+                        _ => (),
+                    }
+                }
+                _ => {
+                    let drag = state.borrow_mut().drag.take();
+                    let Some(drag) = drag else { return };
+                    if drag.pointer != e.pointer_id() {
+                        state.borrow_mut().drag = Some(drag);
+                        return;
+                    }
+                    //a swipe is not a click on the links of the slide
+                    state.borrow_mut().dragged = drag.dx.abs() > 5.0;
+                    let direction = if drag.dx < 0.0 { 1.0 } else { -1.0 };
+                    let progress = match state.borrow().options.transition {
+                        CarouselTransition::Slide if !reduced_motion() => (drag.dx.abs() / drag.width).min(1.0),
+                        _ => 0.0,
+                    };
+                    let neighbor = state.borrow().neighbor(direction);
+                    match neighbor {
+                        Some(neighbor) if drag.dx.abs() > drag.width * 0.2 || (drag.dx.abs() > 30.0 && e.type_() == "pointerup") => {
+                            CarouselHandle::run(&state, neighbor, direction, progress)
+                        }
+                        Some(neighbor) if progress > 0.0 => CarouselHandle::settle(&state, neighbor, direction, progress),
+                        _ => {
+                            let s = state.borrow();
+                            clear(&s.slides[s.current]);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    impl Drop for CarouselHandle {
+        fn drop(&mut self) {
+            let mut s = self.state.borrow_mut();
+            s.autoplay = None;
+            if let Some(animation) = s.animation.take() {
+                animation.cancel();
+            }
+            for element in s.created.drain(..) {
+                element.remove();
+            }
+            for slide in &s.slides {
+                clear(slide);
+                slide.remove_class("active");
+            }
+            for (element, name, value) in s.saved.drain(..).rev() {
+                match value {
+                    Some(value) => element.set_attr(name, &value),
+                    None => element.remove_attr(name),
+                }
+            }
+            if s.added_class {
+                s.root.remove_class("carousel");
+            }
+        }
+    }
+
+    fn button(class: &str, label: &str, text: &str, controls: &str) -> HtmlElement {
+        let button = crate::document().create_html("button");
+        button.set_attr("type", "button");
+        button.add_class(class);
+        button.set_attr("aria-label", label);
+        button.set_attr("aria-controls", controls);
+        button.set_text_content(Some(text));
+        button
+    }
+
+    /// Start a carousel on root without Yew, it lives until the returned CarouselHandle is dropped.
+    /// The slides are the elements with class carousel-slide inside .carousel-track, the data-* attributes of root win on options.
+    /// Arrows and dots are created unless there are already a .carousel-prev, .carousel-next or .carousel-dots.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let root = document().query_selector_html("#news");
+    /// let news = carousel(&root, CarouselOptions::default());
+    /// news.next();
+    /// ```
+    /// ```html
+    /// <div id="news" class="carousel" data-transition="fade" data-autoplay="5000" aria-label="News">
+    ///     <div class="carousel-track">
+    ///         <div class="carousel-slide">...</div>
+    ///         <div class="carousel-slide">...</div>
+    ///     </div>
+    /// </div>
+    /// ```
+    pub fn carousel(root: &HtmlElement, options: CarouselOptions) -> CarouselHandle {
+        crate::style::inject_css("minimal-carousel-style", CAROUSEL_CSS);
+        let options = options.for_element(root);
+        let track = root.query_selector_html(".carousel-track");
+        let list = track.query_selector_list(":scope > .carousel-slide");
+        let slides: Vec<HtmlElement> = (0..list.length()).map(|i| list.get_html(i)).collect();
+
+        let mut saved = Vec::new();
+        let mut save = |element: &HtmlElement, names: &[&'static str]| {
+            for name in names {
+                saved.push((element.clone(), *name, element.get_attribute(name)));
+            }
+        };
+        save(root, &["role", "aria-roledescription", "aria-label"]);
+        save(&track, &["id", "aria-live"]);
+        for slide in &slides {
+            save(slide, &["role", "aria-roledescription", "aria-label", "aria-hidden", "inert"]);
+        }
+        let added_class = !root.has_class("carousel");
+
+        if track.id().is_empty() {
+            let id = NEXT_ID.with(|n| {
+                n.set(n.get() + 1);
+                n.get()
+            });
+            track.set_id(&format!("carousel-track-{id}"));
+        }
+        let track_id = track.id();
+
+        root.add_class("carousel");
+        root.set_attr("role", "region");
+        root.set_attr("aria-roledescription", "carousel");
+        root.set_attr("aria-label", &options.label);
+        for slide in &slides {
+            slide.set_attr("role", "group");
+            slide.set_attr("aria-roledescription", "slide");
+        }
+
+        let mut created = Vec::new();
+        let mut arrows = Vec::new();
+        if options.arrows && slides.len() > 1 {
+            for (class, label, text) in [("carousel-prev", "Previous slide", "‹"), ("carousel-next", "Next slide", "›")] {
+                let arrow = match root.query_selector(&format!(".{class}")).ok().flatten() {
+                    Some(arrow) => arrow.to_html(),
+                    None => {
+                        let arrow = button(class, label, text, &track_id);
+                        root.app_child(arrow.clone().into());
+                        created.push(arrow.clone());
+                        arrow
+                    }
+                };
+                arrows.push(arrow);
+            }
+        }
+        let mut dots = Vec::new();
+        let mut dots_root = None;
+        if options.dots && slides.len() > 1 {
+            let container = match root.query_selector(".carousel-dots").ok().flatten() {
+                Some(container) => container.to_html(),
+                None => {
+                    let container = crate::document().create_html("div");
+                    container.add_class("carousel-dots");
+                    root.app_child(container.clone().into());
+                    created.push(container.clone());
+                    container
+                }
+            };
+            for i in 0..slides.len() {
+                let dot = button("carousel-dot", &format!("Go to slide {}", i + 1), "", &track_id);
+                dot.set_attr("data-index", &i.to_string());
+                container.app_child(dot.clone().into());
+                created.push(dot.clone());
+                dots.push(dot);
+            }
+            dots_root = Some(container);
+        }
+
+        let state = Rc::new(RefCell::new(CarouselState {
+            options: options.clone(),
+            root: root.clone(),
+            track: track.clone(),
+            slides,
+            dots,
+            created,
+            saved,
+            added_class,
+            current: 0,
+            animation: None,
+            drag: None,
+            dragged: false,
+            hovered: false,
+            focused: false,
+            paused: false,
+            autoplay: None,
+        }));
+        state.borrow().update_aria();
+
+        let mut listeners = Vec::new();
+        for (arrow, direction) in arrows.iter().zip([-1.0, 1.0]) {
+            let weak = Rc::downgrade(&state);
+            listeners.push(listen(arrow, "click", move |_e| {
+                if let Some(state) = weak.upgrade() {
+                    CarouselHandle::step(&state, direction);
+                }
+            }));
+        }
+        if let Some(container) = dots_root {
+            let weak = Rc::downgrade(&state);
+            listeners.push(listen(&container, "click", move |e| {
+                let Some(state) = weak.upgrade() else { return };
+                let index = e
+                    .target()
+                    .and_then(|t| t.dyn_into::<Element>().ok())
+                    .and_then(|t| t.closest(".carousel-dot").ok().flatten())
+                    .and_then(|dot| dot.get_attribute("data-index"))
+                    .and_then(|i| i.parse::<usize>().ok());
+                if let Some(index) = index {
+                    CarouselHandle::show(&state, index);
+                }
+            }));
+        }
+        if options.keyboard {
+            let weak = Rc::downgrade(&state);
+            listeners.push(listen(root, "keydown", move |e| {
+                let (Some(state), Some(e)) = (weak.upgrade(), e.dyn_ref::<web_sys::KeyboardEvent>()) else { return };
+                match e.key().as_str() {
+                    "ArrowLeft" => CarouselHandle::step(&state, -1.0),
+                    "ArrowRight" => CarouselHandle::step(&state, 1.0),
+                    _ => return,
+                }
+                e.prevent_default();
+            }));
+        }
+        for (event, hovered) in [("mouseenter", true), ("mouseleave", false)] {
+            let weak = Rc::downgrade(&state);
+            listeners.push(listen(root, event, move |_e| {
+                if let Some(state) = weak.upgrade() {
+                    state.borrow_mut().hovered = hovered;
+                }
+            }));
+        }
+        for event in ["focusin", "focusout"] {
+            let weak = Rc::downgrade(&state);
+            listeners.push(listen(root, event, move |e| {
+                let Some(state) = weak.upgrade() else { return };
+                let mut s = state.borrow_mut();
+                s.focused = match e.dyn_ref::<web_sys::FocusEvent>() {
+                    Some(e) if e.type_() == "focusout" => e
+                        .related_target()
+                        .and_then(|t| t.dyn_into::<web_sys::Node>().ok())
+                        .map(|t| s.root.contains(Some(&t)))
+                        .unwrap_or(false),
+                    _ => true,
+                };
+            }));
+        }
+        if options.swipe {
+            for event in ["pointerdown", "pointermove", "pointerup", "pointercancel"] {
+                let weak = Rc::downgrade(&state);
+                listeners.push(listen(&track, event, move |e| CarouselHandle::on_pointer(&weak, &e)));
+            }
+            let weak = Rc::downgrade(&state);
+            listeners.push(listen(&track, "click", move |e| {
+                let Some(state) = weak.upgrade() else { return };
+                if std::mem::replace(&mut state.borrow_mut().dragged, false) {
+                    e.prevent_default();
+                    e.stop_propagation();
+                }
+            }));
+        }
+        CarouselHandle::restart_autoplay(&state);
+        CarouselHandle {
+            state,
+            _listeners: listeners,
+        }
+    }
+
+    /// Properties of Carousel.
+    #[derive(Properties, PartialEq)]
+    pub struct CarouselProps {
+        #[prop_or_default]
+        pub children: Children,
+        #[prop_or_default]
+        pub options: CarouselOptions,
+        #[prop_or_default]
+        pub class: Classes,
+    }
+
+    /// A carousel of its children, every child is a slide.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use minimal_web::carousel::{Carousel, CarouselOptions, CarouselTransition};
+    ///
+    /// let options = CarouselOptions { transition: CarouselTransition::Fade, autoplay: Some(5000.0), ..Default::default() };
+    /// html!{
+    ///     <Carousel {options}>
+    ///         <img src="one.jpg" alt="One" />
+    ///         <img src="two.jpg" alt="Two" />
+    ///     </Carousel>
+    /// }
+    /// ```
+    #[function_component]
+    pub fn Carousel(props: &CarouselProps) -> Html {
+        let root = use_node_ref();
+        {
+            let root = root.clone();
+            use_effect_with_deps(
+                move |(options, _)| {
+                    let handle = root.cast::<HtmlElement>().map(|root| carousel(&root, options.clone()));
+                    move || drop(handle)
+                },
+                (props.options.clone(), props.children.len()),
+            );
+        }
+        html! {
+            <div ref={root} class={classes!("carousel", props.class.clone())}>
+                <div class="carousel-track">
+                    { for props.children.iter().map(|child| html!{ <div class="carousel-slide">{ child }</div> }) }
+                </div>
+            </div>
+        }
+    }
+}
+
+
+pub mod scroll {
+    use std::cell::{Cell, RefCell};
+    use std::rc::Rc;
+    use yew::prelude::*;
+    use web_sys::{Element, HtmlElement, NodeList};
+    use crate::animation::{reduced_motion, request_frame, Animation, AnimationOptions, Easing};
+    use crate::utils::*;
+
+    thread_local! {
+        //only one scroll animation at a time, a new one replaces the old
+        static SCROLLING: RefCell<Option<Scrolling>> = const { RefCell::new(None) };
+        static NEXT_SCROLL: Cell<u32> = const { Cell::new(0) };
+        static LOCK: RefCell<LockState> = const { RefCell::new(LockState { count: 0, saved: Vec::new() }) };
+    }
+
+    /// Options of smooth_scroll_to.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let options = ScrollOptions { offset: 64.0, easing: Some(Easing::InOutCubic), ..Default::default() };
+    /// window().smooth_scroll_to(&section, options);
+    /// ```
+    #[derive(Clone, Copy, Debug, PartialEq)]
+    pub struct ScrollOptions {
+        /// Space left above the element, for example the height of a fixed header.
+        pub offset: f64,
+        /// Duration in milliseconds, used only with an easing.
+        pub duration: f64,
+        /// None for the native smooth scroll of the browser.
+        pub easing: Option<Easing>,
+    }
+
+    impl Default for ScrollOptions {
+        fn default() -> Self {
+            ScrollOptions {
+                offset: 0.0,
+                duration: 600.0,
+                easing: None,
+            }
+        }
+    }
+
+    //a scroll of smooth_scroll_to, its listeners stop only the scroll with their id
+    struct Scrolling {
+        id: u32,
+        animation: Animation,
+        listeners: Vec<Listener>,
+    }
+
+    //take the running scroll if it's still the one with id
+    fn take_scroll(id: u32) -> Option<Scrolling> {
+        SCROLLING.with(|s| {
+            let mut s = s.borrow_mut();
+            match s.as_ref() {
+                Some(running) if running.id == id => s.take(),
+                _ => None,
+            }
+        })
+    }
+
+    /// Stop the scroll animation started by smooth_scroll_to, if there is one.
+    pub fn stop_scroll() {
+        let running = SCROLLING.with(|s| s.borrow_mut().take());
+        if let Some(running) = running {
+            running.animation.pause();
+        }
+    }
+
+    /// Scroll the page smoothly to element, with reduced motion it jumps.
+    /// With an easing the page is moved on every frame and the user can stop it with the wheel, a touch or a key.
+    pub fn smooth_scroll_to(element: &Element, options: ScrollOptions) {
+        stop_scroll();
+        let window = crate::window();
+        let from = window.get_scroll_y();
+        let root = window.document_element_el();
+        let max = (root.scroll_height() as f64 - window.get_height()).max(0.0);
+        let to = (element.get_bounding_client_rect().top() + from - options.offset).clamp(0.0, max);
+        let Some(easing) = options.easing else {
+            let behavior = if reduced_motion() { web_sys::ScrollBehavior::Instant } else { web_sys::ScrollBehavior::Smooth };
+            if options.offset == 0.0 {
+                //scrollIntoView keeps the scroll-margin-top of the stylesheet
+                let mut native = web_sys::ScrollIntoViewOptions::new();
+                native.behavior(behavior).block(web_sys::ScrollLogicalPosition::Start);
+                element.scroll_into_view_with_scroll_into_view_options(&native);
+            } else {
+                let mut native = web_sys::ScrollToOptions::new();
+                native.top(to).behavior(behavior);
+                window.scroll_to_with_scroll_to_options(&native);
+            }
+            return;
+        };
+        let scroll_x = window.get_scroll_x();
+        let animation_options = AnimationOptions {
+            duration: options.duration,
+            easing,
+            ..Default::default()
+        };
+        let animation = Animation::new(from, to, animation_options, move |y: f64| window.scroll_to_with_x_and_y(scroll_x, y));
+        let id = NEXT_SCROLL.with(|n| {
+            n.set(n.get().wrapping_add(1));
+            n.get()
+        });
+        animation.on_complete(move || drop(take_scroll(id)));
+        SCROLLING.with(|s| {
+            *s.borrow_mut() = Some(Scrolling {
+                id,
+                animation: animation.clone(),
+                listeners: Vec::new(),
+            })
+        });
+        animation.play();
+        //added in the next frame, so the key or the touch that started the scroll doesn't stop it
+        request_frame(move |_| {
+            SCROLLING.with(|s| {
+                let mut s = s.borrow_mut();
+                let Some(running) = s.as_mut().filter(|running| running.id == id) else { return };
+                let document = crate::document();
+                running.listeners = ["wheel", "touchstart", "keydown"]
+                    .iter()
+                    .map(|event| {
+                        listen_passive(&document, event, move |_e| {
+                            request_frame(move |_| {
+                                if let Some(running) = take_scroll(id) {
+                                    running.animation.pause();
+                                }
+                            })
+                        })
+                    })
+                    .collect();
+            })
+        });
+    }
+
+    struct ScrollSpyState {
+        sections: Vec<HtmlElement>,
+        links: Vec<(HtmlElement, String)>,
+        offset: Option<f64>,
+        active: Option<String>,
+        pending: bool,
+        on_change: Option<Box<dyn Fn(Option<String>)>>,
+    }
+
+    /// Links of the section in view marked until dropped, see scroll_spy.
+    pub struct ScrollSpy {
+        state: Rc<RefCell<ScrollSpyState>>,
+        _listeners: Vec<Listener>,
+    }
+
+    fn mark_links(links: &[(HtmlElement, String)], active: Option<&str>) {
+        for (link, target) in links {
+            if Some(target.as_str()) == active {
+                link.add_class("active");
+                link.set_attr("aria-current", "true");
+            } else {
+                link.remove_class("active");
+                link.remove_attr("aria-current");
+            }
+        }
+    }
+
+    impl ScrollSpy {
+        /// Get the id of the section in view.
+        pub fn active(&self) -> Option<String> {
+            self.state.borrow().active.clone()
+        }
+
+        /// Call a function with the id of the section when it changes.
+        pub fn on_change<F: Fn(Option<String>) + 'static>(&self, callback: F) {
+            self.state.borrow_mut().on_change = Some(Box::new(callback));
+        }
+
+        fn update(state: &Rc<RefCell<ScrollSpyState>>) {
+            let window = crate::window();
+            let (active, callback) = {
+                let mut s = state.borrow_mut();
+                s.pending = false;
+                let line = s.offset.unwrap_or_else(|| window.get_height() / 3.0);
+                let at_bottom = window.get_scroll_y() + window.get_height()
+                    >= window.document_element_el().scroll_height() as f64 - 1.0;
+                //the last section that starts above the line, or the last one at the end of the page
+                let mut active = s
+                    .sections
+                    .iter()
+                    .rev()
+                    .find(|section| section.get_bounding_client_rect().top() <= line)
+                    .map(|section| section.id());
+                if at_bottom && !s.sections.is_empty() {
+                    active = s.sections.last().map(|section| section.id());
+                }
+                if active == s.active {
+                    return;
+                }
+                mark_links(&s.links, active.as_deref());
+                s.active = active.clone();
+                (active, s.on_change.take())
+            };
+            if let Some(callback) = callback {
+                callback(active);
+                state.borrow_mut().on_change.get_or_insert(callback);
+            }
+        }
+    }
+
+    impl Drop for ScrollSpy {
+        fn drop(&mut self) {
+            mark_links(&self.state.borrow().links, None);
+        }
+    }
+
+    /// Same as scroll_spy, a section is in view when its top is above offset pixels from the top of the window.
+    pub fn scroll_spy_with(sections: &NodeList, nav_links: &NodeList, offset: Option<f64>) -> ScrollSpy {
+        let sections: Vec<HtmlElement> = (0..sections.length())
+            .map(|i| sections.get_html(i))
+            .filter(|section| !section.id().is_empty())
+            .collect();
+        //a link belongs to the section of the id after # in its href
+        let links = (0..nav_links.length())
+            .filter_map(|i| {
+                let link = nav_links.get_html(i);
+                let href = link.get_attribute("href")?;
+                let (_, id) = href.split_once('#')?;
+                Some((link, id.to_owned()))
+            })
+            .collect();
+        let state = Rc::new(RefCell::new(ScrollSpyState {
+            sections,
+            links,
+            offset,
+            active: None,
+            pending: false,
+            on_change: None,
+        }));
+        ScrollSpy::update(&state);
+        //before the first scroll the hash of the page wins
+        let hash = crate::document().get_hash();
+        if let Some(id) = hash.strip_prefix('#').filter(|id| !id.is_empty()) {
+            let known = state.borrow().sections.iter().any(|section| section.id() == id);
+            if known && state.borrow().active.as_deref() != Some(id) {
+                let mut s = state.borrow_mut();
+                mark_links(&s.links, Some(id));
+                s.active = Some(id.to_owned());
+            }
+        }
+        let window = crate::window();
+        let listeners = ["scroll", "resize"]
+            .iter()
+            .map(|event| {
+                let weak = Rc::downgrade(&state);
+                listen_passive(&window, event, move |_e| {
+                    let Some(state) = weak.upgrade() else { return };
+                    if std::mem::replace(&mut state.borrow_mut().pending, true) {
+                        return;
+                    }
+                    let weak = Rc::downgrade(&state);
+                    request_frame(move |_| {
+                        if let Some(state) = weak.upgrade() {
+                            ScrollSpy::update(&state);
+                        }
+                    });
+                })
+            })
+            .collect();
+        ScrollSpy {
+            state,
+            _listeners: listeners,
+        }
+    }
+
+    /// Add the class active (and aria-current) to the nav link of the section in view, the link of a section has href="#id".
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let document = document();
+    /// let spy = scroll_spy(&document.query_selector_list("section[id]"), &document.query_selector_list("nav a"));
+    /// spy.on_change(|id| gloo::console::log!(id));
+    /// ```
+    pub fn scroll_spy(sections: &NodeList, nav_links: &NodeList) -> ScrollSpy {
+        scroll_spy_with(sections, nav_links, None)
+    }
+
+    /// Run scroll_spy on the elements of two selectors and get the id of the section in view.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let active = use_scroll_spy("section[id]", "nav a");
+    /// ```
+    #[hook]
+    pub fn use_scroll_spy(sections: &str, nav_links: &str) -> Option<String> {
+        let active = use_state(|| None::<String>);
+        {
+            let active = active.clone();
+            use_effect_with_deps(
+                move |(sections, nav_links)| {
+                    let document = crate::document();
+                    let spy = scroll_spy(&document.query_selector_list(sections), &document.query_selector_list(nav_links));
+                    active.set(spy.active());
+                    spy.on_change(move |id| active.set(id));
+                    move || drop(spy)
+                },
+                (sections.to_owned(), nav_links.to_owned()),
+            );
+        }
+        (*active).clone()
+    }
+
+    /// Last direction of the scroll, Idle before the first scroll.
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+    pub enum ScrollDirection {
+        #[default]
+        Idle,
+        Up,
+        Down,
+    }
+
+    /// Position of the scroll of the window or of an element.
+    #[derive(Clone, Copy, Debug, PartialEq, Default)]
+    pub struct ScrollState {
+        pub x: f64,
+        pub y: f64,
+        /// Vertical direction of the last scroll.
+        pub direction: ScrollDirection,
+        /// Vertical progress between 0 and 1, 0 if it can't scroll.
+        pub progress: f64,
+        pub at_top: bool,
+        pub at_bottom: bool,
+    }
+
+    impl ScrollState {
+        /// Measure the scroll of element, or of the window with None, previous gives the direction.
+        pub fn measure(element: Option<&Element>, previous: &ScrollState) -> ScrollState {
+            let (x, y, max) = match element {
+                Some(element) => (
+                    element.scroll_left() as f64,
+                    element.scroll_top() as f64,
+                    (element.scroll_height() - element.client_height()) as f64,
+                ),
+                None => {
+                    let window = crate::window();
+                    let root = window.document_element_el();
+                    (window.get_scroll_x(), window.get_scroll_y(), (root.scroll_height() - root.client_height()) as f64)
+                }
+            };
+            let max = max.max(0.0);
+            let direction = match y - previous.y {
+                delta if delta > 0.0 => ScrollDirection::Down,
+                delta if delta < 0.0 => ScrollDirection::Up,
+                _ => previous.direction,
+            };
+            ScrollState {
+                x,
+                y,
+                direction,
+                progress: if max > 0.0 { (y / max).clamp(0.0, 1.0) } else { 0.0 },
+                at_top: y <= 0.0,
+                //1px of tolerance for the zoom of the browser
+                at_bottom: y >= max - 1.0,
+            }
+        }
+    }
+
+    /// Call a function with the ScrollState at most once per frame until dropped, see use_scroll.
+    pub struct ScrollWatcher {
+        _listeners: Vec<Listener>,
+    }
+
+    struct WatcherState {
+        element: Option<Element>,
+        scroll: ScrollState,
+        pending: bool,
+        callback: Box<dyn FnMut(ScrollState)>,
+    }
+
+    impl ScrollWatcher {
+        /// Watch the scroll of element, or of the window with None.
+        pub fn new<F>(element: Option<Element>, callback: F) -> ScrollWatcher
+        where
+            F: FnMut(ScrollState) + 'static,
+        {
+            let scroll = ScrollState::measure(element.as_ref(), &ScrollState::default());
+            let target: web_sys::EventTarget = match &element {
+                Some(element) => element.clone().into(),
+                None => crate::window().into(),
+            };
+            let state = Rc::new(RefCell::new(WatcherState {
+                element,
+                scroll,
+                pending: false,
+                callback: Box::new(callback),
+            }));
+            let on_event = move |_e| {
+                if std::mem::replace(&mut state.borrow_mut().pending, true) {
+                    return;
+                }
+                let weak = Rc::downgrade(&state);
+                request_frame(move |_| {
+                    let Some(state) = weak.upgrade() else { return };
+                    let mut s = state.borrow_mut();
+                    s.pending = false;
+                    let scroll = ScrollState::measure(s.element.as_ref(), &s.scroll);
+                    if scroll != s.scroll {
+                        s.scroll = scroll;
+                        (s.callback)(scroll);
+                    }
+                });
+            };
+            let on_resize = on_event.clone();
+            ScrollWatcher {
+                _listeners: vec![
+                    listen_passive(&target, "scroll", on_event),
+                    listen_passive(&crate::window(), "resize", on_resize),
+                ],
+            }
+        }
+    }
+
+    /// Get the scroll of the window, the component is updated at most once per frame while it scrolls.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let scroll = use_scroll();
+    /// let hidden = scroll.direction == ScrollDirection::Down && !scroll.at_top;
+    /// html!{
+    ///     <header class={classes!("header", hidden.then_some("hidden"))}></header>
+    ///     <div class="progress" style={format!("width:{}%", scroll.progress * 100.0)}></div>
+    /// }
+    /// ```
+    #[hook]
+    pub fn use_scroll() -> ScrollState {
+        //never attached, so it's always the window
+        let window = use_node_ref();
+        use_scroll_element(window)
+    }
+
+    /// Same as use_scroll for a scrollable element, the window while node is not mounted.
+    #[hook]
+    pub fn use_scroll_element(node: NodeRef) -> ScrollState {
+        let scroll = use_state_eq(|| ScrollState::measure(None, &ScrollState::default()));
+        {
+            let scroll = scroll.clone();
+            use_effect_with_deps(
+                move |node| {
+                    let element = node.cast::<Element>();
+                    scroll.set(ScrollState::measure(element.as_ref(), &scroll));
+                    let watcher = ScrollWatcher::new(element, move |state| scroll.set(state));
+                    move || drop(watcher)
+                },
+                node,
+            );
+        }
+        *scroll
+    }
+
+    //properties of document_element changed by ScrollLock, with value and priority before the first lock
+    const LOCKED_PROPERTIES: [&str; 3] = ["overflow", "padding-right", "--scrollbar-width"];
+
+    struct LockState {
+        count: u32,
+        saved: Vec<(&'static str, String, String)>,
+    }
+
+    /// A guard that keeps the page from scrolling while it lives, for modals and drawers.
+    /// Locks are counted, the page scrolls again when the last one is dropped.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let lock = ScrollLock::acquire();
+    /// //the page doesn't scroll and doesn't move
+    /// drop(lock);
+    /// ```
+    pub struct ScrollLock {
+        _private: (),
+    }
+
+    impl ScrollLock {
+        /// Lock the scroll of the page, the space of the scrollbar is kept with padding-right.
+        /// The width of the scrollbar is also in the css variable --scrollbar-width, for fixed elements.
+        pub fn acquire() -> ScrollLock {
+            LOCK.with(|lock| {
+                let mut lock = lock.borrow_mut();
+                lock.count += 1;
+                if lock.count > 1 {
+                    return;
+                }
+                let window = crate::window();
+                let root = window.document_element_html();
+                let style = root.style();
+                lock.saved = LOCKED_PROPERTIES
+                    .iter()
+                    .map(|property| {
+                        let value = style.get_property_value(property).unwrap_or_default();
+                        let priority = style.get_property_priority(property);
+                        (*property, value, priority)
+                    })
+                    .collect();
+                let scrollbar = (window.get_width() - root.client_width() as f64).max(0.0);
+                let padding = window
+                    .get_computed_style(&root)
+                    .ok()
+                    .flatten()
+                    .and_then(|computed| computed.get_property_value("padding-right").ok())
+                    .and_then(|value| value.trim_end_matches("px").parse::<f64>().ok())
+                    .unwrap_or(0.0);
+                root.set_prop("overflow", "hidden");
+                if scrollbar > 0.0 {
+                    root.set_prop("padding-right", &format!("{}px", padding + scrollbar));
+                }
+                root.set_prop("--scrollbar-width", &format!("{scrollbar}px"));
+            });
+            ScrollLock { _private: () }
+        }
+
+        /// Check if the scroll of the page is locked.
+        pub fn is_locked() -> bool {
+            LOCK.with(|lock| lock.borrow().count > 0)
+        }
+    }
+
+    impl Drop for ScrollLock {
+        fn drop(&mut self) {
+            LOCK.with(|lock| {
+                let mut lock = lock.borrow_mut();
+                lock.count = lock.count.saturating_sub(1);
+                if lock.count > 0 {
+                    return;
+                }
+                let style = crate::window().document_element_html().style();
+                for (property, value, priority) in lock.saved.drain(..) {
+                    if value.is_empty() {
+                        let _ = style.remove_property(property);
+                    } else {
+                        let _ = style.set_property_with_priority(property, &value, &priority);
+                    }
+                }
+            });
+        }
+    }
+
+    /// Lock the scroll of the page while locked is true.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let open = use_state(|| false);
+    /// use_scroll_lock(*open);
+    /// ```
+    #[hook]
+    pub fn use_scroll_lock(locked: bool) {
+        use_effect_with_deps(
+            move |locked| {
+                let lock = locked.then(ScrollLock::acquire);
+                move || drop(lock)
+            },
+            locked,
+        );
+    }
+}
+
+pub mod idb {
+    use serde::de::DeserializeOwned;
+    use serde::Serialize;
+    use std::cell::RefCell;
+    use std::fmt;
+    use std::rc::Rc;
+    use wasm_bindgen::prelude::*;
+    use wasm_bindgen_futures::JsFuture;
+    use web_sys::{Event, EventTarget, IdbDatabase, IdbObjectStore, IdbRequest, IdbTransaction, IdbTransactionMode};
+
+    /// Error of an IndexedDB operation.
+    #[derive(Clone, Debug, PartialEq)]
+    pub enum IdbError {
+        /// IndexedDB is disabled, for example in some private modes.
+        Unavailable,
+        /// There's no more space for the value.
+        QuotaExceeded,
+        /// A unique index or the key already has the value.
+        Constraint(String),
+        /// The value can't be converted to JSON.
+        Serialize(String),
+        /// The saved value is not valid for the type.
+        Parse(String),
+        /// A migration failed and the upgrade was aborted.
+        Migration(String),
+        /// Any other error, with the name and the message of the DOMException.
+        Js(String, String),
+    }
+
+    impl fmt::Display for IdbError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self {
+                IdbError::Unavailable => write!(f, "IndexedDB is not available"),
+                IdbError::QuotaExceeded => write!(f, "the storage quota is exceeded"),
+                IdbError::Constraint(e) => write!(f, "constraint error: {e}"),
+                IdbError::Serialize(e) => write!(f, "the value can't be serialized: {e}"),
+                IdbError::Parse(e) => write!(f, "the saved value can't be parsed: {e}"),
+                IdbError::Migration(e) => write!(f, "the migration failed: {e}"),
+                IdbError::Js(name, message) => write!(f, "{name}: {message}"),
+            }
+        }
+    }
+
+    impl std::error::Error for IdbError {}
+
+    impl From<JsValue> for IdbError {
+        fn from(error: JsValue) -> Self {
+            let read = |key: &str| {
+                js_sys::Reflect::get(&error, &key.into())
+                    .ok()
+                    .and_then(|v| v.as_string())
+                    .unwrap_or_default()
+            };
+            let (name, message) = (read("name"), read("message"));
+            match name.as_str() {
+                "QuotaExceededError" => IdbError::QuotaExceeded,
+                "ConstraintError" => IdbError::Constraint(message),
+                _ => IdbError::Js(name, message),
+            }
+        }
+    }
+
+    //serde values go through JSON, they are plain objects in the database so indexes can read their fields
+    fn to_js<T: Serialize + ?Sized>(value: &T) -> Result<JsValue, IdbError> {
+        let text = serde_json::to_string(value).map_err(|e| IdbError::Serialize(e.to_string()))?;
+        js_sys::JSON::parse(&text).map_err(|e| IdbError::Serialize(format!("{e:?}")))
+    }
+
+    fn from_js<T: DeserializeOwned>(value: &JsValue) -> Result<T, IdbError> {
+        let text = js_sys::JSON::stringify(value)
+            .ok()
+            .and_then(|t| t.as_string())
+            .unwrap_or_else(|| "null".to_owned());
+        serde_json::from_str(&text).map_err(|e| IdbError::Parse(e.to_string()))
+    }
+
+    fn from_js_list<T: DeserializeOwned>(value: &JsValue) -> Result<Vec<T>, IdbError> {
+        js_sys::Array::from(value).iter().map(|v| from_js(&v)).collect()
+    }
+
+    fn request_error(request: &IdbRequest) -> IdbError {
+        request
+            .error()
+            .ok()
+            .flatten()
+            .map(|e| IdbError::from(JsValue::from(e)))
+            .unwrap_or_else(|| IdbError::Js("UnknownError".to_owned(), String::new()))
+    }
+
+    //listeners of wait_events, removed on drop so a dropped future doesn't leave them without their closure
+    struct EventsGuard<'a> {
+        target: &'a EventTarget,
+        events: &'a [&'a str],
+        closure: Closure<dyn FnMut(Event)>,
+    }
+
+    impl Drop for EventsGuard<'_> {
+        fn drop(&mut self) {
+            for event in self.events {
+                let _ = self
+                    .target
+                    .remove_event_listener_with_callback(event, self.closure.as_ref().unchecked_ref());
+            }
+        }
+    }
+
+    //wait until on_event returns Some, the listeners are removed after it or when the future is dropped
+    async fn wait_events<F>(target: &EventTarget, events: &[&str], mut on_event: F) -> Result<JsValue, IdbError>
+    where
+        F: FnMut(&Event) -> Option<Result<JsValue, IdbError>> + 'static,
+    {
+        let result = Rc::new(RefCell::new(None));
+        let mut resolve = None;
+        let promise = js_sys::Promise::new(&mut |res, _| resolve = Some(res));
+        let resolve = resolve.expect("the Promise executor is synchronous");
+        let closure = {
+            let result = result.clone();
+            Closure::<dyn FnMut(Event)>::new(move |e: Event| {
+                if result.borrow().is_some() {
+                    return;
+                }
+                if let Some(value) = on_event(&e) {
+                    *result.borrow_mut() = Some(value);
+                    let _ = resolve.call0(&JsValue::NULL);
+                }
+            })
+        };
+        for event in events {
+            let _ = target.add_event_listener_with_callback(event, closure.as_ref().unchecked_ref());
+        }
+        let guard = EventsGuard { target, events, closure };
+        let _ = JsFuture::from(promise).await;
+        drop(guard);
+        let result = result.borrow_mut().take();
+        result.unwrap_or(Err(IdbError::Js("AbortError".to_owned(), String::new())))
+    }
+
+    async fn wait(request: IdbRequest) -> Result<JsValue, IdbError> {
+        let target = request.clone();
+        wait_events(&target, &["success", "error"], move |e| match e.type_().as_str() {
+            "success" => Some(request.result().map_err(IdbError::from)),
+            _ => Some(Err(request_error(&request))),
+        })
+        .await
+    }
+
+    async fn wait_transaction(transaction: IdbTransaction) -> Result<(), IdbError> {
+        let target = transaction.clone();
+        wait_events(&target, &["complete", "error", "abort"], move |e| match e.type_().as_str() {
+            "complete" => Some(Ok(JsValue::UNDEFINED)),
+            _ => Some(Err(transaction
+                .error()
+                .map(|e| IdbError::from(JsValue::from(e)))
+                .unwrap_or_else(|| IdbError::Js("AbortError".to_owned(), "the transaction was aborted".to_owned())))),
+        })
+        .await
+        .map(|_| ())
+    }
+
+    /// Mode of a transaction.
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    pub enum TransactionMode {
+        ReadOnly,
+        ReadWrite,
+    }
+
+    impl From<TransactionMode> for IdbTransactionMode {
+        fn from(mode: TransactionMode) -> Self {
+            match mode {
+                TransactionMode::ReadOnly => IdbTransactionMode::Readonly,
+                TransactionMode::ReadWrite => IdbTransactionMode::Readwrite,
+            }
+        }
+    }
+
+    /// Options of Upgrade::create_store.
+    #[derive(Clone, Debug, Default, PartialEq, Eq)]
+    pub struct StoreOptions {
+        /// Field of the values used as key, None when keys are given with put_with_key.
+        pub key_path: Option<String>,
+        /// Generate increasing number keys for values without one.
+        pub auto_increment: bool,
+    }
+
+    /// The database during a migration, see Database::open.
+    pub struct Upgrade {
+        db: IdbDatabase,
+        transaction: IdbTransaction,
+        old_version: u32,
+    }
+
+    /// An object store during a migration.
+    pub struct UpgradeStore {
+        store: IdbObjectStore,
+    }
+
+    impl Upgrade {
+        /// Version of the database before the upgrade, 0 if it's new.
+        pub fn old_version(&self) -> u32 {
+            self.old_version
+        }
+
+        /// Create an object store.
+        pub fn create_store(&self, name: &str, options: StoreOptions) -> Result<UpgradeStore, IdbError> {
+            let mut parameters = web_sys::IdbObjectStoreParameters::new();
+            parameters.auto_increment(options.auto_increment);
+            if let Some(key_path) = &options.key_path {
+                parameters.key_path(Some(&key_path.into()));
+            }
+            let store = self.db.create_object_store_with_optional_parameters(name, &parameters)?;
+            Ok(UpgradeStore { store })
+        }
+
+        /// Get an object store created by an older migration, for example to add an index.
+        pub fn store(&self, name: &str) -> Result<UpgradeStore, IdbError> {
+            Ok(UpgradeStore {
+                store: self.transaction.object_store(name)?,
+            })
+        }
+
+        /// Delete an object store and its values.
+        pub fn delete_store(&self, name: &str) -> Result<(), IdbError> {
+            Ok(self.db.delete_object_store(name)?)
+        }
+    }
+
+    impl UpgradeStore {
+        /// Create an index on a field of the values, unique rejects two values with the same field.
+        pub fn create_index(&self, name: &str, key_path: &str, unique: bool) -> Result<&Self, IdbError> {
+            let mut parameters = web_sys::IdbIndexParameters::new();
+            parameters.unique(unique);
+            self.store
+                .create_index_with_str_and_optional_parameters(name, key_path, &parameters)?;
+            Ok(self)
+        }
+
+        /// Delete an index.
+        pub fn delete_index(&self, name: &str) -> Result<&Self, IdbError> {
+            self.store.delete_index(name)?;
+            Ok(self)
+        }
+    }
+
+    /// A migration, the migration at index i upgrades the database from version i to i + 1.
+    pub type Migration = fn(&Upgrade) -> Result<(), IdbError>;
+
+    /// An open IndexedDB database, cheap to clone.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use minimal_web::idb::{Database, StoreOptions};
+    ///
+    /// let db = Database::open("app", &[
+    ///     |upgrade| {
+    ///         let notes = upgrade.create_store("notes", StoreOptions { key_path: Some("id".to_owned()), auto_increment: true })?;
+    ///         notes.create_index("by_tag", "tag", false)?;
+    ///         Ok(())
+    ///     },
+    ///     |upgrade| upgrade.store("notes")?.create_index("by_date", "date", false).map(|_| ()),
+    /// ]).await?;
+    /// ```
+    #[derive(Clone, Debug)]
+    pub struct Database {
+        db: IdbDatabase,
+    }
+
+    //armed while Database::open waits, if the future is dropped the request is still running:
+    //a late upgrade is aborted so the version doesn't change without its migrations, a late connection is closed
+    struct PendingOpen(Option<web_sys::IdbOpenDbRequest>);
+
+    impl Drop for PendingOpen {
+        fn drop(&mut self) {
+            let Some(request) = self.0.take() else { return };
+            let mut options = web_sys::AddEventListenerOptions::new();
+            options.once(true);
+            let on_upgrade = {
+                let request = request.clone();
+                Closure::once_into_js(move |_: Event| {
+                    if let Some(transaction) = request.transaction() {
+                        let _ = transaction.abort();
+                    }
+                })
+            };
+            let on_success = {
+                let request = request.clone();
+                Closure::once_into_js(move |_: Event| {
+                    if let Ok(db) = request.result() {
+                        db.unchecked_into::<IdbDatabase>().close();
+                    }
+                })
+            };
+            let _ = request.add_event_listener_with_callback_and_add_event_listener_options("upgradeneeded", on_upgrade.unchecked_ref(), &options);
+            let _ = request.add_event_listener_with_callback_and_add_event_listener_options("success", on_success.unchecked_ref(), &options);
+        }
+    }
+
+    impl Database {
+        /// Open a database, its version is the number of migrations and the missing ones run in order.
+        /// If another tab keeps an older version open, it waits until that tab closes it, see open_with_blocked.
+        /// The connections opened by this function close themselves when a newer version is opened.
+        pub async fn open(name: &str, migrations: &[Migration]) -> Result<Database, IdbError> {
+            Database::open_with_blocked(name, migrations, || {}).await
+        }
+
+        /// Same as open(), on_blocked is called when another tab keeps an older version open,
+        /// for example to ask the user to close it. The future keeps waiting until the upgrade can run.
+        ///
+        /// # Examples
+        ///
+        /// ```ignore
+        /// let db = Database::open_with_blocked("app", MIGRATIONS, || show_message("Close the other tabs of the app")).await?;
+        /// ```
+        pub async fn open_with_blocked<F>(name: &str, migrations: &[Migration], on_blocked: F) -> Result<Database, IdbError>
+        where
+            F: Fn() + 'static,
+        {
+            let factory = crate::window()
+                .indexed_db()
+                .ok()
+                .flatten()
+                .ok_or(IdbError::Unavailable)?;
+            let request = factory.open_with_u32(name, migrations.len().max(1) as u32)?;
+            let mut pending = PendingOpen(Some(request.clone()));
+            let migrations = migrations.to_vec();
+            let target = request.clone();
+            let db = wait_events(&target, &["upgradeneeded", "success", "error", "blocked"], move |e| {
+                match e.type_().as_str() {
+                    "upgradeneeded" => {
+                        let upgrade = Upgrade {
+                            db: request.result().ok()?.unchecked_into(),
+                            transaction: request.transaction()?,
+                            old_version: e
+                                .dyn_ref::<web_sys::IdbVersionChangeEvent>()
+                                .map_or(0.0, |e| e.old_version()) as u32,
+                        };
+                        for migration in migrations.iter().skip(upgrade.old_version as usize) {
+                            if let Err(error) = migration(&upgrade) {
+                                let _ = upgrade.transaction.abort();
+                                return Some(Err(IdbError::Migration(error.to_string())));
+                            }
+                        }
+                        None
+                    }
+                    "success" => Some(request.result().map_err(IdbError::from)),
+                    "blocked" => {
+                        on_blocked();
+                        None
+                    }
+                    _ => Some(Err(request_error(&request))),
+                }
+            })
+            .await;
+            pending.0 = None;
+            let db = db?;
+            let db: IdbDatabase = db.unchecked_into();
+            //a newer version opened in another tab waits for this connection, it's closed so the upgrade can run
+            let on_version_change = {
+                let db = db.clone();
+                Closure::once_into_js(move |_: Event| db.close())
+            };
+            db.set_onversionchange(Some(on_version_change.unchecked_ref()));
+            Ok(Database { db })
+        }
+
+        /// Delete a database, the open connections must be closed first.
+        pub async fn delete(name: &str) -> Result<(), IdbError> {
+            let factory = crate::window()
+                .indexed_db()
+                .ok()
+                .flatten()
+                .ok_or(IdbError::Unavailable)?;
+            wait(factory.delete_database(name)?.unchecked_into()).await.map(|_| ())
+        }
+
+        /// Name of the database.
+        pub fn name(&self) -> String {
+            self.db.name()
+        }
+
+        /// Version of the database, the number of migrations.
+        pub fn version(&self) -> u32 {
+            self.db.version() as u32
+        }
+
+        /// An object store, every call runs in its own transaction.
+        pub fn store(&self, name: &str) -> IdbStore {
+            IdbStore {
+                db: self.db.clone(),
+                name: name.to_owned(),
+                transaction: None,
+            }
+        }
+
+        /// Start a transaction on some stores, its calls are committed or aborted together.
+        ///
+        /// # Examples
+        ///
+        /// ```ignore
+        /// let transaction = db.transaction(&["accounts"], TransactionMode::ReadWrite)?;
+        /// let accounts = transaction.store("accounts");
+        /// accounts.put(&from).await?;
+        /// accounts.put(&to).await?;
+        /// transaction.done().await?;
+        /// ```
+        pub fn transaction(&self, stores: &[&str], mode: TransactionMode) -> Result<Transaction, IdbError> {
+            let names: js_sys::Array = stores.iter().map(|s| JsValue::from_str(s)).collect();
+            let transaction = self
+                .db
+                .transaction_with_str_sequence_and_mode(&names, mode.into())?;
+            Ok(Transaction {
+                db: self.db.clone(),
+                transaction,
+            })
+        }
+
+        /// Close the connection, the calls after it fail.
+        /// It's closed as well when another tab opens a newer version of the database.
+        pub fn close(&self) {
+            self.db.close();
+        }
+    }
+
+    /// A transaction on some object stores.
+    ///
+    /// It's committed by the browser when it has no more pending calls, so don't await anything else than its stores
+    /// before done.
+    pub struct Transaction {
+        db: IdbDatabase,
+        transaction: IdbTransaction,
+    }
+
+    impl Transaction {
+        /// An object store of the transaction.
+        pub fn store(&self, name: &str) -> IdbStore {
+            IdbStore {
+                db: self.db.clone(),
+                name: name.to_owned(),
+                transaction: Some(self.transaction.clone()),
+            }
+        }
+
+        /// Cancel every change of the transaction.
+        pub fn abort(&self) -> Result<(), IdbError> {
+            Ok(self.transaction.abort()?)
+        }
+
+        /// Wait until the transaction is committed.
+        pub async fn done(self) -> Result<(), IdbError> {
+            wait_transaction(self.transaction).await
+        }
+    }
+
+    /// An object store with serde values.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let notes = db.store("notes");
+    /// notes.put(&Note { id: 1, tag: "rust".to_owned() }).await?;
+    /// let note: Option<Note> = notes.get(&1).await?;
+    /// let all: Vec<Note> = notes.get_all().await?;
+    /// notes.delete(&1).await?;
+    /// ```
+    #[derive(Clone, Debug)]
+    pub struct IdbStore {
+        db: IdbDatabase,
+        name: String,
+        transaction: Option<IdbTransaction>,
+    }
+
+    impl IdbStore {
+        fn object_store(&self, mode: TransactionMode) -> Result<IdbObjectStore, IdbError> {
+            let transaction = match &self.transaction {
+                Some(transaction) => transaction.clone(),
+                None => self.db.transaction_with_str_and_mode(&self.name, mode.into())?,
+            };
+            Ok(transaction.object_store(&self.name)?)
+        }
+
+        /// Name of the store.
+        pub fn name(&self) -> &str {
+            &self.name
+        }
+
+        /// Get the value of a key.
+        pub async fn get<T: DeserializeOwned>(&self, key: &(impl Serialize + ?Sized)) -> Result<Option<T>, IdbError> {
+            let value = wait(self.object_store(TransactionMode::ReadOnly)?.get(&to_js(key)?)?).await?;
+            if value.is_undefined() {
+                return Ok(None);
+            }
+            from_js(&value).map(Some)
+        }
+
+        /// Get every value, ordered by key.
+        pub async fn get_all<T: DeserializeOwned>(&self) -> Result<Vec<T>, IdbError> {
+            let values = wait(self.object_store(TransactionMode::ReadOnly)?.get_all()?).await?;
+            from_js_list(&values)
+        }
+
+        /// Get the values with a key between lower and upper, both included.
+        pub async fn get_range<T: DeserializeOwned>(
+            &self,
+            lower: &(impl Serialize + ?Sized),
+            upper: &(impl Serialize + ?Sized),
+        ) -> Result<Vec<T>, IdbError> {
+            let range = web_sys::IdbKeyRange::bound(&to_js(lower)?, &to_js(upper)?)?;
+            let values = wait(self.object_store(TransactionMode::ReadOnly)?.get_all_with_key(&range)?).await?;
+            from_js_list(&values)
+        }
+
+        /// Add or replace a value in a store with a key path, returns its key.
+        pub async fn put<T: Serialize + ?Sized>(&self, value: &T) -> Result<JsValue, IdbError> {
+            wait(self.object_store(TransactionMode::ReadWrite)?.put(&to_js(value)?)?).await
+        }
+
+        /// Add or replace the value of a key in a store without key path.
+        pub async fn put_with_key<T: Serialize + ?Sized>(&self, key: &(impl Serialize + ?Sized), value: &T) -> Result<(), IdbError> {
+            let store = self.object_store(TransactionMode::ReadWrite)?;
+            wait(store.put_with_key(&to_js(value)?, &to_js(key)?)?).await.map(|_| ())
+        }
+
+        /// Delete the value of a key.
+        pub async fn delete(&self, key: &(impl Serialize + ?Sized)) -> Result<(), IdbError> {
+            wait(self.object_store(TransactionMode::ReadWrite)?.delete(&to_js(key)?)?).await.map(|_| ())
+        }
+
+        /// Delete every value.
+        pub async fn clear(&self) -> Result<(), IdbError> {
+            wait(self.object_store(TransactionMode::ReadWrite)?.clear()?).await.map(|_| ())
+        }
+
+        /// Number of values.
+        pub async fn count(&self) -> Result<u32, IdbError> {
+            let count = wait(self.object_store(TransactionMode::ReadOnly)?.count()?).await?;
+            Ok(count.as_f64().unwrap_or(0.0) as u32)
+        }
+
+        /// An index of the store, created by a migration.
+        pub fn index(&self, name: &str) -> IdbIndex {
+            IdbIndex {
+                store: self.clone(),
+                name: name.to_owned(),
+            }
+        }
+    }
+
+    /// An index of an object store, to query values by a field.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let by_tag = db.store("notes").index("by_tag");
+    /// let rust: Vec<Note> = by_tag.get_all(&"rust").await?;
+    /// let this_week: Vec<Note> = db.store("notes").index("by_date").get_range(&monday, &sunday).await?;
+    /// ```
+    #[derive(Clone, Debug)]
+    pub struct IdbIndex {
+        store: IdbStore,
+        name: String,
+    }
+
+    impl IdbIndex {
+        fn index(&self) -> Result<web_sys::IdbIndex, IdbError> {
+            Ok(self.store.object_store(TransactionMode::ReadOnly)?.index(&self.name)?)
+        }
+
+        /// Get the first value with this field.
+        pub async fn get<T: DeserializeOwned>(&self, key: &(impl Serialize + ?Sized)) -> Result<Option<T>, IdbError> {
+            let value = wait(self.index()?.get(&to_js(key)?)?).await?;
+            if value.is_undefined() {
+                return Ok(None);
+            }
+            from_js(&value).map(Some)
+        }
+
+        /// Get every value with this field.
+        pub async fn get_all<T: DeserializeOwned>(&self, key: &(impl Serialize + ?Sized)) -> Result<Vec<T>, IdbError> {
+            let values = wait(self.index()?.get_all_with_key(&to_js(key)?)?).await?;
+            from_js_list(&values)
+        }
+
+        /// Get the values with a field between lower and upper, both included, ordered by this field.
+        pub async fn get_range<T: DeserializeOwned>(
+            &self,
+            lower: &(impl Serialize + ?Sized),
+            upper: &(impl Serialize + ?Sized),
+        ) -> Result<Vec<T>, IdbError> {
+            let range = web_sys::IdbKeyRange::bound(&to_js(lower)?, &to_js(upper)?)?;
+            let values = wait(self.index()?.get_all_with_key(&range)?).await?;
+            from_js_list(&values)
+        }
+
+        /// Number of values with this field.
+        pub async fn count(&self, key: &(impl Serialize + ?Sized)) -> Result<u32, IdbError> {
+            let count = wait(self.index()?.count_with_key(&to_js(key)?)?).await?;
+            Ok(count.as_f64().unwrap_or(0.0) as u32)
+        }
+    }
+}
+
+pub mod http {
+    use crate::utils::{listen, timeout, Listener, Timeout};
+    use serde::de::DeserializeOwned;
+    use serde::Serialize;
+    use std::cell::Cell;
+    use std::fmt;
+    use std::rc::Rc;
+    use wasm_bindgen::prelude::*;
+    use wasm_bindgen_futures::JsFuture;
+    use web_sys::{AbortController, AbortSignal, Headers, RequestInit, UrlSearchParams};
+
+    /// Error of a request, the network, the status and the body are kept apart.
+    #[derive(Clone, Debug, PartialEq)]
+    pub enum HttpError {
+        /// The request didn't reach the server: offline, DNS, CORS...
+        Network(String),
+        /// The timeout of the request elapsed.
+        Timeout,
+        /// The request was cancelled with its AbortHandle.
+        Aborted,
+        /// The server answered with a status that is not 2xx.
+        Status { status: u16, status_text: String, body: String },
+        /// The query or the body can't be serialized.
+        Serialize(String),
+        /// The body of the response is not valid for the type.
+        Parse(String),
+    }
+
+    impl fmt::Display for HttpError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self {
+                HttpError::Network(e) => write!(f, "network error: {e}"),
+                HttpError::Timeout => write!(f, "the request timed out"),
+                HttpError::Aborted => write!(f, "the request was aborted"),
+                HttpError::Status { status, status_text, .. } => write!(f, "the server answered {status} {status_text}"),
+                HttpError::Serialize(e) => write!(f, "the request can't be serialized: {e}"),
+                HttpError::Parse(e) => write!(f, "the response can't be parsed: {e}"),
+            }
+        }
+    }
+
+    impl std::error::Error for HttpError {}
+
+    impl HttpError {
+        /// Status of the response, if the server answered.
+        pub fn status(&self) -> Option<u16> {
+            match self {
+                HttpError::Status { status, .. } => Some(*status),
+                _ => None,
+            }
+        }
+    }
+
+    fn js_message(error: &JsValue) -> String {
+        js_sys::Reflect::get(error, &"message".into())
+            .ok()
+            .and_then(|m| m.as_string())
+            .or_else(|| error.as_string())
+            .unwrap_or_else(|| format!("{error:?}"))
+    }
+
+    //key/value pairs of a struct or a map, sequences repeat the key and None is skipped
+    fn pairs<T: Serialize + ?Sized>(value: &T) -> Result<Vec<(String, String)>, HttpError> {
+        fn text(value: &serde_json::Value) -> Option<String> {
+            match value {
+                serde_json::Value::Null => None,
+                serde_json::Value::String(s) => Some(s.clone()),
+                other => Some(other.to_string()),
+            }
+        }
+        let value = serde_json::to_value(value).map_err(|e| HttpError::Serialize(e.to_string()))?;
+        let serde_json::Value::Object(map) = value else {
+            return Err(HttpError::Serialize("the query and the form must be a struct or a map".to_owned()));
+        };
+        let mut pairs = Vec::new();
+        for (key, value) in map {
+            match value {
+                serde_json::Value::Array(values) => pairs.extend(values.iter().filter_map(text).map(|v| (key.clone(), v))),
+                value => pairs.extend(text(&value).map(|v| (key, v))),
+            }
+        }
+        Ok(pairs)
+    }
+
+    fn search_params(pairs: &[(String, String)]) -> UrlSearchParams {
+        let params = UrlSearchParams::new().expect("URLSearchParams is available");
+        for (key, value) in pairs {
+            params.append(key, value);
+        }
+        params
+    }
+
+    /// Cancel requests from anywhere, one handle can cancel several requests.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let handle = AbortHandle::new();
+    /// let request = http::get("/api/search").query(&[("q", "rust")]).abort_with(&handle).send();
+    /// handle.abort(); // the request fails with HttpError::Aborted
+    /// ```
+    #[derive(Clone, Debug)]
+    pub struct AbortHandle {
+        controller: AbortController,
+    }
+
+    impl AbortHandle {
+        pub fn new() -> Self {
+            AbortHandle {
+                controller: AbortController::new().expect("AbortController is available"),
+            }
+        }
+
+        /// Cancel every request sent with this handle, also the next ones.
+        pub fn abort(&self) {
+            self.controller.abort();
+        }
+
+        pub fn is_aborted(&self) -> bool {
+            self.controller.signal().aborted()
+        }
+    }
+
+    impl Default for AbortHandle {
+        fn default() -> Self {
+            Self::new()
+        }
+    }
+
+    enum Body {
+        Empty,
+        Json(String),
+        Form(Vec<(String, String)>),
+        Text(String),
+    }
+
+    /// A request being built, see get, post, put, patch and delete.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let users: Vec<User> = http::get("/api/users")
+    ///     .query(&Filter { page: 2, tag: None })
+    ///     .header("Accept-Language", "fr")
+    ///     .timeout(5000)
+    ///     .send()
+    ///     .await?
+    ///     .json()
+    ///     .await?;
+    /// http::post("/api/users").json(&new_user).send().await?;
+    /// ```
+    #[must_use = "the request is sent by send()"]
+    pub struct RequestBuilder {
+        method: String,
+        url: String,
+        query: Vec<(String, String)>,
+        headers: Vec<(String, String)>,
+        body: Body,
+        timeout: Option<u32>,
+        abort: Option<AbortHandle>,
+        error: Option<HttpError>,
+    }
+
+    /// A request with any method.
+    pub fn request(method: &str, url: &str) -> RequestBuilder {
+        RequestBuilder {
+            method: method.to_uppercase(),
+            url: url.to_owned(),
+            query: Vec::new(),
+            headers: Vec::new(),
+            body: Body::Empty,
+            timeout: None,
+            abort: None,
+            error: None,
+        }
+    }
+
+    pub fn get(url: &str) -> RequestBuilder {
+        request("GET", url)
+    }
+
+    pub fn post(url: &str) -> RequestBuilder {
+        request("POST", url)
+    }
+
+    pub fn put(url: &str) -> RequestBuilder {
+        request("PUT", url)
+    }
+
+    pub fn patch(url: &str) -> RequestBuilder {
+        request("PATCH", url)
+    }
+
+    pub fn delete(url: &str) -> RequestBuilder {
+        request("DELETE", url)
+    }
+
+    impl RequestBuilder {
+        /// Add the fields of a struct, a map or a list of pairs to the query string.
+        pub fn query<T: Serialize + ?Sized>(mut self, params: &T) -> Self {
+            match pairs_or_list(params) {
+                Ok(pairs) => self.query.extend(pairs),
+                Err(error) => {
+                    self.error.get_or_insert(error);
+                }
+            }
+            self
+        }
+
+        pub fn header(mut self, name: &str, value: &str) -> Self {
+            self.headers.push((name.to_owned(), value.to_owned()));
+            self
+        }
+
+        /// Send a JSON body, with Content-Type application/json if header() doesn't give another one.
+        pub fn json<T: Serialize + ?Sized>(mut self, body: &T) -> Self {
+            match serde_json::to_string(body) {
+                Ok(json) => self.body = Body::Json(json),
+                Err(error) => {
+                    self.error.get_or_insert(HttpError::Serialize(error.to_string()));
+                }
+            }
+            self
+        }
+
+        /// Send a form body, application/x-www-form-urlencoded.
+        pub fn form<T: Serialize + ?Sized>(mut self, body: &T) -> Self {
+            match pairs_or_list(body) {
+                Ok(pairs) => self.body = Body::Form(pairs),
+                Err(error) => {
+                    self.error.get_or_insert(error);
+                }
+            }
+            self
+        }
+
+        /// Send a text body.
+        pub fn text(mut self, body: &str) -> Self {
+            self.body = Body::Text(body.to_owned());
+            self
+        }
+
+        /// Fail with HttpError::Timeout after millis milliseconds, reading the body included.
+        pub fn timeout(mut self, millis: u32) -> Self {
+            self.timeout = Some(millis);
+            self
+        }
+
+        /// Cancel the request when the handle is aborted.
+        pub fn abort_with(mut self, handle: &AbortHandle) -> Self {
+            self.abort = Some(handle.clone());
+            self
+        }
+
+        /// The full url, with the query string.
+        pub fn url(&self) -> String {
+            if self.query.is_empty() {
+                return self.url.clone();
+            }
+            let query = String::from(search_params(&self.query).to_string());
+            let separator = if self.url.contains('?') { '&' } else { '?' };
+            format!("{}{separator}{query}", self.url)
+        }
+
+        /// Send the request, a status that is not 2xx is an HttpError::Status with the body.
+        pub async fn send(self) -> Result<Response, HttpError> {
+            if let Some(error) = self.error {
+                return Err(error);
+            }
+            let url = self.url();
+            let controller = AbortController::new().map_err(|e| HttpError::Network(js_message(&e)))?;
+            let signal = controller.signal();
+            let mut guard = Guard {
+                timed_out: Rc::new(Cell::new(false)),
+                signal: signal.clone(),
+                _timeout: None,
+                _abort: None,
+            };
+            if let Some(millis) = self.timeout {
+                let (controller, timed_out) = (controller.clone(), guard.timed_out.clone());
+                guard._timeout = Some(timeout(millis, move || {
+                    timed_out.set(true);
+                    controller.abort();
+                }));
+            }
+            if let Some(handle) = &self.abort {
+                if handle.is_aborted() {
+                    return Err(HttpError::Aborted);
+                }
+                let controller = controller.clone();
+                guard._abort = Some(listen(&handle.controller.signal(), "abort", move |_| controller.abort()));
+            }
+
+            let headers = Headers::new().map_err(|e| HttpError::Network(js_message(&e)))?;
+            let mut init = RequestInit::new();
+            init.method(&self.method).signal(Some(&signal));
+            match &self.body {
+                Body::Empty => {}
+                Body::Json(json) => {
+                    init.body(Some(&JsValue::from_str(json)));
+                }
+                Body::Form(pairs) => {
+                    init.body(Some(&search_params(pairs)));
+                }
+                Body::Text(text) => {
+                    init.body(Some(&JsValue::from_str(text)));
+                }
+            }
+            for (name, value) in &self.headers {
+                headers
+                    .append(name, value)
+                    .map_err(|e| HttpError::Serialize(js_message(&e)))?;
+            }
+            //a Content-Type given with header() wins over the default one
+            if matches!(self.body, Body::Json(_)) && !headers.has("Content-Type").unwrap_or(false) {
+                let _ = headers.set("Content-Type", "application/json");
+            }
+            init.headers(&headers);
+
+            let response = JsFuture::from(crate::window().fetch_with_str_and_init(&url, &init))
+                .await
+                .map_err(|e| guard.error(&e))?;
+            let response = Response {
+                inner: response.unchecked_into(),
+                guard,
+            };
+            if !response.ok() {
+                let body = response.text_inner().await.unwrap_or_default();
+                return Err(HttpError::Status {
+                    status: response.status(),
+                    status_text: response.inner.status_text(),
+                    body,
+                });
+            }
+            Ok(response)
+        }
+    }
+
+    fn pairs_or_list<T: Serialize + ?Sized>(value: &T) -> Result<Vec<(String, String)>, HttpError> {
+        //a list of pairs like [("q", "rust")] is serialized as an array of arrays
+        if let Ok(serde_json::Value::Array(items)) = serde_json::to_value(value) {
+            return items
+                .iter()
+                .map(|item| match item.as_array().map(Vec::as_slice) {
+                    Some([key, value]) => Ok((
+                        key.as_str().map_or_else(|| key.to_string(), str::to_owned),
+                        value.as_str().map_or_else(|| value.to_string(), str::to_owned),
+                    )),
+                    _ => Err(HttpError::Serialize("a list must contain (key, value) pairs".to_owned())),
+                })
+                .collect();
+        }
+        pairs(value)
+    }
+
+    //keeps the timeout and the abort listener until the body is read
+    struct Guard {
+        timed_out: Rc<Cell<bool>>,
+        signal: AbortSignal,
+        _timeout: Option<Timeout>,
+        _abort: Option<Listener>,
+    }
+
+    impl Guard {
+        fn error(&self, error: &JsValue) -> HttpError {
+            if self.timed_out.get() {
+                HttpError::Timeout
+            } else if self.signal.aborted() {
+                HttpError::Aborted
+            } else {
+                HttpError::Network(js_message(error))
+            }
+        }
+    }
+
+    /// A 2xx response.
+    pub struct Response {
+        inner: web_sys::Response,
+        guard: Guard,
+    }
+
+    impl Response {
+        pub fn status(&self) -> u16 {
+            self.inner.status()
+        }
+
+        pub fn ok(&self) -> bool {
+            self.inner.ok()
+        }
+
+        /// The url after redirects.
+        pub fn url(&self) -> String {
+            self.inner.url()
+        }
+
+        /// A header of the response, None if it's missing.
+        pub fn header(&self, name: &str) -> Option<String> {
+            self.inner.headers().get(name).ok().flatten()
+        }
+
+        async fn text_inner(&self) -> Result<String, HttpError> {
+            let promise = self.inner.text().map_err(|e| self.guard.error(&e))?;
+            let text = JsFuture::from(promise).await.map_err(|e| self.guard.error(&e))?;
+            Ok(text.as_string().unwrap_or_default())
+        }
+
+        pub async fn text(self) -> Result<String, HttpError> {
+            self.text_inner().await
+        }
+
+        /// Parse the body as JSON.
+        pub async fn json<T: DeserializeOwned>(self) -> Result<T, HttpError> {
+            let text = self.text_inner().await?;
+            serde_json::from_str(&text).map_err(|e| HttpError::Parse(e.to_string()))
+        }
+
+        pub async fn bytes(self) -> Result<Vec<u8>, HttpError> {
+            let promise = self.inner.array_buffer().map_err(|e| self.guard.error(&e))?;
+            let buffer = JsFuture::from(promise).await.map_err(|e| self.guard.error(&e))?;
+            Ok(js_sys::Uint8Array::new(&buffer).to_vec())
+        }
+    }
+}
+
+pub mod query {
+    use crate::utils::{listen, window};
+    use std::any::Any;
+    use std::cell::{Cell, RefCell};
+    use std::collections::HashMap;
+    use std::future::Future;
+    use std::rc::Rc;
+    use wasm_bindgen_futures::spawn_local;
+    use yew::prelude::*;
+
+    struct Subscriber {
+        id: u32,
+        notify: Rc<dyn Fn()>,
+        refetch: Rc<dyn Fn()>,
+    }
+
+    #[derive(Default)]
+    struct Entry {
+        data: Option<Rc<dyn Any>>,
+        error: Option<Rc<dyn Any>>,
+        //Date.now() of the last answer, 0 when it's invalidated
+        updated: f64,
+        fetching: bool,
+        //invalidated while fetching, the answer is older than the invalidation
+        refetch_pending: bool,
+        subscribers: Vec<Subscriber>,
+    }
+
+    thread_local! {
+        static CACHE: RefCell<HashMap<String, Entry>> = RefCell::new(HashMap::new());
+        static NEXT_ID: Cell<u32> = const { Cell::new(0) };
+    }
+
+    //the callbacks are called after the cache is released, a render can read it again
+    fn notify(key: &str) {
+        let callbacks: Vec<Rc<dyn Fn()>> = CACHE.with(|c| {
+            c.borrow()
+                .get(key)
+                .map(|e| e.subscribers.iter().map(|s| s.notify.clone()).collect())
+                .unwrap_or_default()
+        });
+        for callback in callbacks {
+            callback();
+        }
+    }
+
+    fn is_stale(key: &str, stale_time: f64) -> bool {
+        CACHE.with(|c| {
+            !c.borrow()
+                .get(key)
+                .is_some_and(|e| e.updated != 0.0 && js_sys::Date::now() - e.updated < stale_time)
+        })
+    }
+
+    //the request of a key is sent once, the next calls wait for it
+    fn fetch<T, E, Fut>(key: &str, future: impl FnOnce() -> Fut)
+    where
+        T: 'static,
+        E: 'static,
+        Fut: Future<Output = Result<T, E>> + 'static,
+    {
+        let started = CACHE.with(|c| {
+            let mut cache = c.borrow_mut();
+            let entry = cache.entry(key.to_owned()).or_default();
+            !std::mem::replace(&mut entry.fetching, true)
+        });
+        if !started {
+            return;
+        }
+        notify(key);
+        let future = future();
+        let key = key.to_owned();
+        spawn_local(async move {
+            let result = future.await;
+            let refetch = CACHE.with(|c| {
+                let mut cache = c.borrow_mut();
+                let entry = cache.entry(key.clone()).or_default();
+                entry.fetching = false;
+                //the data is shown but stays stale, it's fetched again
+                let pending = std::mem::take(&mut entry.refetch_pending);
+                entry.updated = if pending { 0.0 } else { js_sys::Date::now() };
+                match result {
+                    Ok(data) => {
+                        entry.data = Some(Rc::new(data));
+                        entry.error = None;
+                    }
+                    //the last data is kept with the error
+                    Err(error) => entry.error = Some(Rc::new(error)),
+                }
+                pending.then(|| entry.subscribers.first().map(|s| s.refetch.clone())).flatten()
+            });
+            notify(&key);
+            if let Some(refetch) = refetch {
+                refetch();
+            }
+        });
+    }
+
+    /// Mark a key as stale, it's fetched again now if a component uses it, or on its next use.
+    /// If the key is being fetched, it's fetched again when the request ends.
+    pub fn invalidate(key: &str) {
+        let refetch = CACHE.with(|c| {
+            let mut cache = c.borrow_mut();
+            let entry = cache.get_mut(key)?;
+            entry.updated = 0.0;
+            if entry.fetching {
+                entry.refetch_pending = true;
+                return None;
+            }
+            entry.subscribers.first().map(|s| s.refetch.clone())
+        });
+        if let Some(refetch) = refetch {
+            refetch();
+        }
+    }
+
+    /// Replace the data of a key, for example with the answer of a mutation.
+    pub fn set_query_data<T: 'static>(key: &str, data: T) {
+        CACHE.with(|c| {
+            let mut cache = c.borrow_mut();
+            let entry = cache.entry(key.to_owned()).or_default();
+            entry.data = Some(Rc::new(data));
+            entry.error = None;
+            entry.updated = js_sys::Date::now();
+        });
+        notify(key);
+    }
+
+    /// Options of use_query_with.
+    #[derive(Clone, Debug, PartialEq)]
+    pub struct QueryOptions {
+        /// Milliseconds the data is fresh, a stale key is fetched again on mount, focus and reconnect.
+        pub stale_time: f64,
+        pub refetch_on_focus: bool,
+        pub refetch_on_reconnect: bool,
+    }
+
+    impl Default for QueryOptions {
+        fn default() -> Self {
+            QueryOptions {
+                stale_time: 0.0,
+                refetch_on_focus: true,
+                refetch_on_reconnect: true,
+            }
+        }
+    }
+
+    /// State of a query, the last data is kept while it's fetched again.
+    pub struct QueryState<T, E = crate::http::HttpError> {
+        pub data: Option<Rc<T>>,
+        /// Error of the last request, cleared by the next success.
+        pub error: Option<Rc<E>>,
+        /// There's no data yet and the first request is pending.
+        pub loading: bool,
+        /// A request is pending, also in background when there's data.
+        pub fetching: bool,
+        /// Fetch again, even if the data is fresh.
+        pub refetch: Callback<()>,
+    }
+
+    impl<T, E> Clone for QueryState<T, E> {
+        fn clone(&self) -> Self {
+            QueryState {
+                data: self.data.clone(),
+                error: self.error.clone(),
+                loading: self.loading,
+                fetching: self.fetching,
+                refetch: self.refetch.clone(),
+            }
+        }
+    }
+
+    /// Same as use_query_with with the default options.
+    #[hook]
+    pub fn use_query<T, E, F, Fut>(key: &str, fetcher: F) -> QueryState<T, E>
+    where
+        T: 'static,
+        E: 'static,
+        F: Fn() -> Fut + 'static,
+        Fut: Future<Output = Result<T, E>> + 'static,
+    {
+        use_query_with(key, QueryOptions::default(), fetcher)
+    }
+
+    /// Fetch data once for every component with the same key, stale-while-revalidate.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let users = use_query("users", || async { http::get("/api/users").send().await?.json::<Vec<User>>().await });
+    /// if users.loading {
+    ///     return html! { <Spinner /> };
+    /// }
+    /// if let Some(error) = &users.error {
+    ///     gloo::console::error!(error.to_string());
+    /// }
+    /// ```
+    #[hook]
+    pub fn use_query_with<T, E, F, Fut>(key: &str, options: QueryOptions, fetcher: F) -> QueryState<T, E>
+    where
+        T: 'static,
+        E: 'static,
+        F: Fn() -> Fut + 'static,
+        Fut: Future<Output = Result<T, E>> + 'static,
+    {
+        let update = use_force_update();
+        let id = *use_state(|| {
+            NEXT_ID.with(|n| {
+                n.set(n.get() + 1);
+                n.get()
+            })
+        });
+        //the last fetcher is used, it can capture the props of this render
+        let latest = use_mut_ref(|| None::<F>);
+        *latest.borrow_mut() = Some(fetcher);
+        let refetch: Rc<dyn Fn()> = {
+            let (key, latest) = (key.to_owned(), latest.clone());
+            Rc::new(move || {
+                if let Some(fetcher) = latest.borrow().as_ref() {
+                    fetch(&key, fetcher);
+                }
+            })
+        };
+
+        {
+            let refetch = refetch.clone();
+            use_effect_with_deps(
+                move |(key, options): &(String, QueryOptions)| {
+                    CACHE.with(|c| {
+                        c.borrow_mut().entry(key.clone()).or_default().subscribers.push(Subscriber {
+                            id,
+                            notify: Rc::new(move || update.force_update()),
+                            refetch: refetch.clone(),
+                        })
+                    });
+                    if is_stale(key, options.stale_time) {
+                        refetch();
+                    }
+                    let on_event = |enabled: bool| {
+                        let (key, stale_time, refetch) = (key.clone(), options.stale_time, refetch.clone());
+                        enabled.then_some(move |_| {
+                            if is_stale(&key, stale_time) {
+                                refetch();
+                            }
+                        })
+                    };
+                    let listeners = [
+                        on_event(options.refetch_on_focus).map(|f| listen(&window(), "focus", f)),
+                        on_event(options.refetch_on_reconnect).map(|f| listen(&window(), "online", f)),
+                    ];
+                    let key = key.clone();
+                    move || {
+                        drop(listeners);
+                        CACHE.with(|c| {
+                            if let Some(entry) = c.borrow_mut().get_mut(&key) {
+                                entry.subscribers.retain(|s| s.id != id);
+                            }
+                        });
+                    }
+                },
+                (key.to_owned(), options),
+            );
+        }
+
+        let (data, error, fetching, cached) = CACHE.with(|c| match c.borrow().get(key) {
+            Some(entry) => (
+                entry.data.clone().and_then(|d| d.downcast::<T>().ok()),
+                entry.error.clone().and_then(|e| e.downcast::<E>().ok()),
+                entry.fetching,
+                entry.updated != 0.0 || entry.data.is_some(),
+            ),
+            None => (None, None, false, false),
+        });
+        QueryState {
+            loading: data.is_none() && error.is_none() && (fetching || !cached),
+            data,
+            error,
+            fetching,
+            refetch: Callback::from(move |_| refetch()),
+        }
+    }
+
+    /// State of a mutation.
+    pub struct MutationState<I, T, E = crate::http::HttpError> {
+        /// Answer of the last mutation.
+        pub data: Option<Rc<T>>,
+        pub error: Option<Rc<E>>,
+        pub loading: bool,
+        /// Send the mutation with this input.
+        pub run: Callback<I>,
+    }
+
+    impl<I, T, E> Clone for MutationState<I, T, E> {
+        fn clone(&self) -> Self {
+            MutationState {
+                data: self.data.clone(),
+                error: self.error.clone(),
+                loading: self.loading,
+                run: self.run.clone(),
+            }
+        }
+    }
+
+    struct MutationResult<T, E> {
+        data: Option<Rc<T>>,
+        error: Option<Rc<E>>,
+        loading: bool,
+    }
+
+    /// Send a change to the server, the keys of invalidates are fetched again after a success.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let add = use_mutation(&["todos"], |todo: Todo| async move {
+    ///     http::post("/api/todos").json(&todo).send().await?.json::<Todo>().await
+    /// });
+    /// let onclick = { let run = add.run.clone(); move |_| run.emit(Todo::new("Buy milk")) };
+    /// ```
+    #[hook]
+    pub fn use_mutation<I, T, E, F, Fut>(invalidates: &[&str], mutator: F) -> MutationState<I, T, E>
+    where
+        I: 'static,
+        T: 'static,
+        E: 'static,
+        F: Fn(I) -> Fut + 'static,
+        Fut: Future<Output = Result<T, E>> + 'static,
+    {
+        let state = use_state(|| MutationResult::<T, E> {
+            data: None,
+            error: None,
+            loading: false,
+        });
+        let latest = use_mut_ref(|| None::<(F, Vec<String>)>);
+        *latest.borrow_mut() = Some((mutator, invalidates.iter().map(|k| k.to_string()).collect()));
+
+        let run = {
+            let state = state.clone();
+            Callback::from(move |input: I| {
+                let Some((future, keys)) = latest.borrow().as_ref().map(|(mutator, keys)| (mutator(input), keys.clone())) else {
+                    return;
+                };
+                state.set(MutationResult {
+                    data: state.data.clone(),
+                    error: None,
+                    loading: true,
+                });
+                let state = state.clone();
+                spawn_local(async move {
+                    match future.await {
+                        Ok(data) => {
+                            state.set(MutationResult {
+                                data: Some(Rc::new(data)),
+                                error: None,
+                                loading: false,
+                            });
+                            for key in &keys {
+                                invalidate(key);
+                            }
+                        }
+                        Err(error) => state.set(MutationResult {
+                            data: None,
+                            error: Some(Rc::new(error)),
+                            loading: false,
+                        }),
+                    }
+                });
+            })
+        };
+        MutationState {
+            data: state.data.clone(),
+            error: state.error.clone(),
+            loading: state.loading,
+            run,
+        }
+    }
+}
+
+pub mod socket {
+    use crate::utils::{interval, listen, timeout, Interval, Listener, Timeout};
+    use serde::de::DeserializeOwned;
+    use serde::Serialize;
+    use std::cell::RefCell;
+    use std::collections::VecDeque;
+    use std::fmt;
+    use std::future::Future;
+    use std::pin::Pin;
+    use std::rc::Rc;
+    use std::task::{Context, Poll, Waker};
+    use wasm_bindgen::prelude::*;
+    use wasm_bindgen_futures::spawn_local;
+    use web_sys::{BinaryType, MessageEvent, WebSocket};
+    use yew::prelude::*;
+
+    /// Error of a socket.
+    #[derive(Clone, Debug, PartialEq)]
+    pub enum SocketError {
+        /// The message can't be converted to JSON.
+        Serialize(String),
+        /// The message received is not valid for the type.
+        Parse(String),
+        /// Too many messages wait for the connection, see SocketOptions::buffer_limit.
+        BufferFull,
+        /// The socket was closed with close() or stopped reconnecting.
+        Closed,
+    }
+
+    impl fmt::Display for SocketError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self {
+                SocketError::Serialize(e) => write!(f, "the message can't be serialized: {e}"),
+                SocketError::Parse(e) => write!(f, "the message can't be parsed: {e}"),
+                SocketError::BufferFull => write!(f, "the buffer of outgoing messages is full"),
+                SocketError::Closed => write!(f, "the socket is closed"),
+            }
+        }
+    }
+
+    impl std::error::Error for SocketError {}
+
+    /// A message of the socket.
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    pub enum Message {
+        Text(String),
+        Binary(Vec<u8>),
+    }
+
+    /// State of the connection.
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    pub enum SocketState {
+        Connecting,
+        Open,
+        /// The connection was lost, attempt starts at 1.
+        Reconnecting { attempt: u32 },
+        Closed,
+    }
+
+    /// Options of Socket::connect.
+    #[derive(Clone, Debug, PartialEq)]
+    pub struct SocketOptions {
+        pub protocols: Vec<String>,
+        /// Reconnect when the connection is lost.
+        pub reconnect: bool,
+        /// Delay before the first reconnection, doubled after every failure.
+        pub min_delay: u32,
+        pub max_delay: u32,
+        /// None to never stop reconnecting.
+        pub max_attempts: Option<u32>,
+        /// Milliseconds between two heartbeats, None to send none.
+        pub heartbeat: Option<u32>,
+        /// Sent with every heartbeat, an echo of it is not given to recv.
+        pub heartbeat_message: String,
+        /// Answer of the server to the heartbeat, it's not given to recv.
+        /// When it's set the connection is restarted after two heartbeats without message.
+        pub heartbeat_reply: Option<String>,
+        /// Maximum number of messages kept while disconnected.
+        pub buffer_limit: usize,
+    }
+
+    impl Default for SocketOptions {
+        fn default() -> Self {
+            SocketOptions {
+                protocols: Vec::new(),
+                reconnect: true,
+                min_delay: 500,
+                max_delay: 30_000,
+                max_attempts: None,
+                heartbeat: None,
+                heartbeat_message: "ping".to_owned(),
+                heartbeat_reply: None,
+                buffer_limit: 1000,
+            }
+        }
+    }
+
+    struct Inner {
+        url: String,
+        options: SocketOptions,
+        ws: Option<WebSocket>,
+        state: SocketState,
+        outbox: VecDeque<Message>,
+        inbox: VecDeque<Message>,
+        //every task waiting in recv, they are all woken and the first one gets the message
+        wakers: Vec<Waker>,
+        last_seen: f64,
+        listeners: Vec<Listener>,
+        retry: Option<Timeout>,
+        heartbeat: Option<Interval>,
+        on_state: Option<Rc<dyn Fn(SocketState)>>,
+    }
+
+    impl Drop for Inner {
+        fn drop(&mut self) {
+            if let Some(ws) = self.ws.take() {
+                let _ = ws.close();
+            }
+        }
+    }
+
+    //a closure can't be dropped while it runs, the replaced listeners and timers are dropped after it
+    fn drop_later<T: 'static>(value: T) {
+        spawn_local(async move { drop(value) });
+    }
+
+    fn set_state(inner: &Rc<RefCell<Inner>>, state: SocketState) {
+        let (on_state, wakers) = {
+            let mut inner = inner.borrow_mut();
+            if inner.state == state {
+                return;
+            }
+            inner.state = state;
+            let wakers = if state == SocketState::Closed { std::mem::take(&mut inner.wakers) } else { Vec::new() };
+            (inner.on_state.clone(), wakers)
+        };
+        for waker in wakers {
+            waker.wake();
+        }
+        if let Some(on_state) = on_state {
+            on_state(state);
+        }
+    }
+
+    fn send_now(ws: &WebSocket, message: &Message) -> bool {
+        match message {
+            Message::Text(text) => ws.send_with_str(text).is_ok(),
+            Message::Binary(bytes) => ws.send_with_u8_array(bytes).is_ok(),
+        }
+    }
+
+    fn on_open(inner: &Rc<RefCell<Inner>>) {
+        {
+            let mut this = inner.borrow_mut();
+            this.last_seen = js_sys::Date::now();
+            if let Some(ws) = this.ws.clone() {
+                while let Some(message) = this.outbox.pop_front() {
+                    if !send_now(&ws, &message) {
+                        this.outbox.push_front(message);
+                        break;
+                    }
+                }
+            }
+            if let Some(millis) = this.options.heartbeat {
+                let weak = Rc::downgrade(inner);
+                let old = this.heartbeat.replace(interval(millis, move || {
+                    let Some(inner) = weak.upgrade() else { return };
+                    let inner = inner.borrow();
+                    let Some(ws) = &inner.ws else { return };
+                    //without a reply a quiet server is not a lost connection
+                    let alive = inner.options.heartbeat_reply.is_none() || js_sys::Date::now() - inner.last_seen <= 2.0 * millis as f64;
+                    if !alive {
+                        //the close event reconnects
+                        let _ = ws.close();
+                    } else {
+                        let _ = ws.send_with_str(&inner.options.heartbeat_message);
+                    }
+                }));
+                drop_later(old);
+            }
+        }
+        set_state(inner, SocketState::Open);
+    }
+
+    fn on_message(inner: &Rc<RefCell<Inner>>, event: &MessageEvent) {
+        let data = event.data();
+        let message = match data.as_string() {
+            Some(text) => Message::Text(text),
+            None => Message::Binary(js_sys::Uint8Array::new(&data).to_vec()),
+        };
+        let wakers = {
+            let mut inner = inner.borrow_mut();
+            inner.last_seen = js_sys::Date::now();
+            if let Message::Text(text) = &message {
+                let options = &inner.options;
+                let echo = options.heartbeat.is_some() && *text == options.heartbeat_message;
+                if echo || options.heartbeat_reply.as_ref() == Some(text) {
+                    return;
+                }
+            }
+            inner.inbox.push_back(message);
+            std::mem::take(&mut inner.wakers)
+        };
+        for waker in wakers {
+            waker.wake();
+        }
+    }
+
+    fn on_close(inner: &Rc<RefCell<Inner>>) {
+        let next = {
+            let mut this = inner.borrow_mut();
+            this.ws = None;
+            drop_later(this.heartbeat.take());
+            let attempt = match this.state {
+                SocketState::Closed => return,
+                SocketState::Reconnecting { attempt } => attempt + 1,
+                _ => 1,
+            };
+            let options = &this.options;
+            if !options.reconnect || options.max_attempts.is_some_and(|max| attempt > max) {
+                None
+            } else {
+                //exponential backoff with jitter, so the clients don't come back all at once
+                let delay = (options.min_delay as f64 * 2f64.powi(attempt as i32 - 1)).min(options.max_delay as f64);
+                let delay = delay * (0.5 + js_sys::Math::random() / 2.0);
+                let weak = Rc::downgrade(inner);
+                let old = this.retry.replace(timeout(delay as u32, move || {
+                    if let Some(inner) = weak.upgrade() {
+                        open(&inner);
+                    }
+                }));
+                drop_later(old);
+                Some(SocketState::Reconnecting { attempt })
+            }
+        };
+        set_state(inner, next.unwrap_or(SocketState::Closed));
+    }
+
+    //close the connection and stop the timers, the state is set by the caller
+    fn shutdown(inner: &Rc<RefCell<Inner>>) {
+        let mut inner = inner.borrow_mut();
+        if let Some(ws) = inner.ws.take() {
+            let _ = ws.close();
+        }
+        drop_later((
+            std::mem::take(&mut inner.listeners),
+            inner.retry.take(),
+            inner.heartbeat.take(),
+        ));
+        inner.outbox.clear();
+    }
+
+    fn open(inner: &Rc<RefCell<Inner>>) {
+        let ws = {
+            let this = inner.borrow();
+            let protocols: js_sys::Array = this.options.protocols.iter().map(|p| JsValue::from_str(p)).collect();
+            WebSocket::new_with_str_sequence(&this.url, &protocols)
+        };
+        let Ok(ws) = ws else {
+            //the constructor throws for an invalid url, it would fail again, so there's no reconnection
+            shutdown(inner);
+            return set_state(inner, SocketState::Closed);
+        };
+        ws.set_binary_type(BinaryType::Arraybuffer);
+        let weak = Rc::downgrade(inner);
+        let handler = |callback: fn(&Rc<RefCell<Inner>>, &web_sys::Event)| {
+            let weak = weak.clone();
+            move |e: web_sys::Event| {
+                if let Some(inner) = weak.upgrade() {
+                    callback(&inner, &e);
+                }
+            }
+        };
+        let listeners = vec![
+            listen(&ws, "open", handler(|inner, _| on_open(inner))),
+            listen(&ws, "message", handler(|inner, e| on_message(inner, e.unchecked_ref()))),
+            listen(&ws, "close", handler(|inner, _| on_close(inner))),
+        ];
+        let mut this = inner.borrow_mut();
+        this.ws = Some(ws);
+        drop_later(std::mem::replace(&mut this.listeners, listeners));
+    }
+
+    /// A WebSocket that reconnects, buffers the messages while disconnected and sends heartbeats, cheap to clone.
+    ///
+    /// The connection is closed when every clone is dropped.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let socket = Socket::connect("wss://example.com/chat", SocketOptions::default());
+    /// socket.send(&Chat { text: "hello".to_owned() })?;
+    /// wasm_bindgen_futures::spawn_local(async move {
+    ///     while let Some(message) = socket.recv::<Chat>().await {
+    ///         match message {
+    ///             Ok(chat) => gloo::console::log!(chat.text),
+    ///             Err(error) => gloo::console::error!(error.to_string()),
+    ///         }
+    ///     }
+    /// });
+    /// ```
+    #[derive(Clone)]
+    pub struct Socket {
+        inner: Rc<RefCell<Inner>>,
+    }
+
+    impl Socket {
+        /// Start the connection, the messages sent before it's open are buffered.
+        pub fn connect(url: &str, options: SocketOptions) -> Socket {
+            let inner = Rc::new(RefCell::new(Inner {
+                url: url.to_owned(),
+                options,
+                ws: None,
+                state: SocketState::Connecting,
+                outbox: VecDeque::new(),
+                inbox: VecDeque::new(),
+                wakers: Vec::new(),
+                last_seen: 0.0,
+                listeners: Vec::new(),
+                retry: None,
+                heartbeat: None,
+                on_state: None,
+            }));
+            open(&inner);
+            Socket { inner }
+        }
+
+        pub fn state(&self) -> SocketState {
+            self.inner.borrow().state
+        }
+
+        /// Call f when the state changes, it replaces the previous callback.
+        pub fn on_state<F: Fn(SocketState) + 'static>(&self, f: F) {
+            self.inner.borrow_mut().on_state = Some(Rc::new(f));
+        }
+
+        /// Send a message, or keep it until the connection is open.
+        pub fn send_message(&self, message: Message) -> Result<(), SocketError> {
+            let mut inner = self.inner.borrow_mut();
+            if inner.state == SocketState::Closed {
+                return Err(SocketError::Closed);
+            }
+            if inner.state == SocketState::Open {
+                if let Some(ws) = &inner.ws {
+                    if send_now(ws, &message) {
+                        return Ok(());
+                    }
+                }
+            }
+            if inner.outbox.len() >= inner.options.buffer_limit {
+                return Err(SocketError::BufferFull);
+            }
+            inner.outbox.push_back(message);
+            Ok(())
+        }
+
+        /// Send a value as JSON text.
+        pub fn send<T: Serialize + ?Sized>(&self, value: &T) -> Result<(), SocketError> {
+            let text = serde_json::to_string(value).map_err(|e| SocketError::Serialize(e.to_string()))?;
+            self.send_message(Message::Text(text))
+        }
+
+        pub fn send_bytes(&self, bytes: &[u8]) -> Result<(), SocketError> {
+            self.send_message(Message::Binary(bytes.to_vec()))
+        }
+
+        /// Wait for the next message, None when the socket is closed.
+        /// Clones can wait at the same time, every message is given to one of them.
+        pub fn recv_message(&self) -> impl Future<Output = Option<Message>> {
+            Recv { inner: self.inner.clone() }
+        }
+
+        /// Wait for the next message parsed from JSON, text or binary, None when the socket is closed.
+        pub async fn recv<T: DeserializeOwned>(&self) -> Option<Result<T, SocketError>> {
+            let parsed = match self.recv_message().await? {
+                Message::Text(text) => serde_json::from_str(&text),
+                Message::Binary(bytes) => serde_json::from_slice(&bytes),
+            };
+            Some(parsed.map_err(|e| SocketError::Parse(e.to_string())))
+        }
+
+        /// Close the connection for good, the pending recv return None.
+        pub fn close(&self) {
+            shutdown(&self.inner);
+            set_state(&self.inner, SocketState::Closed);
+        }
+    }
+
+    struct Recv {
+        inner: Rc<RefCell<Inner>>,
+    }
+
+    impl Future for Recv {
+        type Output = Option<Message>;
+
+        fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+            let mut inner = self.inner.borrow_mut();
+            if let Some(message) = inner.inbox.pop_front() {
+                Poll::Ready(Some(message))
+            } else if inner.state == SocketState::Closed {
+                Poll::Ready(None)
+            } else {
+                if !inner.wakers.iter().any(|w| w.will_wake(cx.waker())) {
+                    inner.wakers.push(cx.waker().clone());
+                }
+                Poll::Pending
+            }
+        }
+    }
+
+    /// Handle of use_websocket.
+    pub struct UseWebSocketHandle<T> {
+        pub state: SocketState,
+        /// Last message received, the messages that can't be parsed are skipped.
+        pub last_message: Option<Rc<T>>,
+        socket: Rc<RefCell<Option<Socket>>>,
+    }
+
+    impl<T> Clone for UseWebSocketHandle<T> {
+        fn clone(&self) -> Self {
+            UseWebSocketHandle {
+                state: self.state,
+                last_message: self.last_message.clone(),
+                socket: self.socket.clone(),
+            }
+        }
+    }
+
+    impl<T> UseWebSocketHandle<T> {
+        /// Send a value as JSON text.
+        pub fn send<M: Serialize + ?Sized>(&self, value: &M) -> Result<(), SocketError> {
+            self.socket.borrow().as_ref().ok_or(SocketError::Closed)?.send(value)
+        }
+
+        /// The socket, None before the first effect and after unmount.
+        pub fn socket(&self) -> Option<Socket> {
+            self.socket.borrow().clone()
+        }
+    }
+
+    /// Connect a socket while the component is mounted, it reconnects when url or options change.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let chat = use_websocket::<Chat>("wss://example.com/chat", SocketOptions::default());
+    /// let online = chat.state == SocketState::Open;
+    /// let onclick = { let chat = chat.clone(); move |_| { let _ = chat.send(&Chat::new("hello")); } };
+    /// ```
+    #[hook]
+    pub fn use_websocket<T>(url: &str, options: SocketOptions) -> UseWebSocketHandle<T>
+    where
+        T: DeserializeOwned + 'static,
+    {
+        let state = use_state_eq(|| SocketState::Connecting);
+        let last_message = use_state(|| None::<Rc<T>>);
+        let socket = use_mut_ref(|| None::<Socket>);
+        {
+            let (state, last_message, slot) = (state.clone(), last_message.clone(), socket.clone());
+            use_effect_with_deps(
+                move |(url, options): &(String, SocketOptions)| {
+                    let socket = Socket::connect(url, options.clone());
+                    state.set(socket.state());
+                    socket.on_state(move |s| state.set(s));
+                    let reader = socket.clone();
+                    spawn_local(async move {
+                        while let Some(message) = reader.recv::<T>().await {
+                            if let Ok(message) = message {
+                                last_message.set(Some(Rc::new(message)));
+                            }
+                        }
+                    });
+                    *slot.borrow_mut() = Some(socket.clone());
+                    move || {
+                        slot.borrow_mut().take();
+                        socket.close();
+                    }
+                },
+                (url.to_owned(), options),
+            );
+        }
+        UseWebSocketHandle {
+            state: *state,
+            last_message: (*last_message).clone(),
+            socket,
+        }
+    }
+}
@@ -0,0 +1,97 @@
+#![cfg(target_arch = "wasm32")]
+
+use minimal_web::http::{self, AbortHandle, HttpError};
+use minimal_web::{timeout, window};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen_test::*;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+//replace window.fetch, the body gets (url, init) and returns a Promise of a Response
+//the last init is kept in window.lastInit
+fn mock_fetch(body: &str) {
+    let fetch = js_sys::Function::new_with_args("url, init", &format!("window.lastInit = init; {body}"));
+    js_sys::Reflect::set(&window(), &"fetch".into(), &fetch).unwrap();
+}
+
+//a fetch that never answers and rejects with an AbortError like the browser when the signal is aborted
+fn mock_pending_fetch() {
+    mock_fetch(
+        "return new Promise((_, reject) => init.signal.addEventListener('abort', () => reject(new DOMException('aborted', 'AbortError'))));",
+    );
+}
+
+fn last_header(name: &str) -> Option<String> {
+    let init = js_sys::Reflect::get(&window(), &"lastInit".into()).unwrap();
+    let headers: web_sys::Headers = js_sys::Reflect::get(&init, &"headers".into()).unwrap().unchecked_into();
+    headers.get(name).unwrap()
+}
+
+#[wasm_bindgen_test]
+async fn decodes_json() {
+    mock_fetch(r#"return Promise.resolve(new Response('{"id":1,"tags":["a","b"]}', { status: 200 }));"#);
+    let response = http::get("/api/notes").send().await.unwrap();
+    assert_eq!(response.status(), 200);
+    let note: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(note, serde_json::json!({ "id": 1, "tags": ["a", "b"] }));
+}
+
+#[wasm_bindgen_test]
+async fn status_error_keeps_the_body() {
+    mock_fetch(r#"return Promise.resolve(new Response('missing note', { status: 404, statusText: 'Not Found' }));"#);
+    let error = http::get("/api/notes/2").send().await.err().unwrap();
+    assert_eq!(
+        error,
+        HttpError::Status {
+            status: 404,
+            status_text: "Not Found".to_owned(),
+            body: "missing note".to_owned(),
+        }
+    );
+    assert_eq!(error.status(), Some(404));
+}
+
+#[wasm_bindgen_test]
+async fn rejected_fetch_is_a_network_error() {
+    mock_fetch("return Promise.reject(new TypeError('Failed to fetch'));");
+    let error = http::get("/api/notes").send().await.err().unwrap();
+    assert_eq!(error, HttpError::Network("Failed to fetch".to_owned()));
+}
+
+#[wasm_bindgen_test]
+async fn timeout_elapses() {
+    mock_pending_fetch();
+    let error = http::get("/api/slow").timeout(20).send().await.err().unwrap();
+    assert_eq!(error, HttpError::Timeout);
+}
+
+#[wasm_bindgen_test]
+async fn abort_handle_cancels() {
+    mock_pending_fetch();
+    let handle = AbortHandle::new();
+    let _abort = {
+        let handle = handle.clone();
+        timeout(20, move || handle.abort())
+    };
+    let error = http::get("/api/slow").abort_with(&handle).send().await.err().unwrap();
+    assert_eq!(error, HttpError::Aborted);
+    assert!(handle.is_aborted());
+    //the next requests with the handle are cancelled before fetch
+    let error = http::get("/api/slow").abort_with(&handle).send().await.err().unwrap();
+    assert_eq!(error, HttpError::Aborted);
+}
+
+#[wasm_bindgen_test]
+async fn json_content_type() {
+    mock_fetch("return Promise.resolve(new Response('', { status: 204 }));");
+    http::post("/api/notes").json(&[1, 2]).send().await.unwrap();
+    assert_eq!(last_header("Content-Type").as_deref(), Some("application/json"));
+
+    http::patch("/api/notes/1")
+        .header("Content-Type", "application/merge-patch+json")
+        .json(&serde_json::json!({ "tag": "rust" }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(last_header("Content-Type").as_deref(), Some("application/merge-patch+json"));
+}
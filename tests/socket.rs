@@ -0,0 +1,171 @@
+#![cfg(target_arch = "wasm32")]
+
+//these tests need the echo server of examples/echo_server.rs:
+//cargo run --example echo_server, then wasm-pack test --headless --firefox
+//ECHO_SERVER changes its url at build time, ws://127.0.0.1:9001 by default
+
+use minimal_web::socket::{Message, Socket, SocketError, SocketOptions, SocketState};
+use minimal_web::window;
+use std::cell::RefCell;
+use std::rc::Rc;
+use wasm_bindgen_futures::{spawn_local, JsFuture};
+use wasm_bindgen_test::*;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+const ECHO_SERVER: &str = match option_env!("ECHO_SERVER") {
+    Some(url) => url,
+    None => "ws://127.0.0.1:9001",
+};
+
+fn text(text: &str) -> Message {
+    Message::Text(text.to_owned())
+}
+
+async fn sleep(millis: i32) {
+    let promise = js_sys::Promise::new(&mut |resolve, _| {
+        window()
+            .set_timeout_with_callback_and_timeout_and_arguments_0(&resolve, millis)
+            .unwrap();
+    });
+    JsFuture::from(promise).await.unwrap();
+}
+
+async fn wait_state(socket: &Socket, state: SocketState) {
+    for _ in 0..200 {
+        if socket.state() == state {
+            return;
+        }
+        sleep(10).await;
+    }
+    panic!("the socket is {:?}, not {state:?}", socket.state());
+}
+
+//the reconnection waits 25 to 50ms, long enough for wait_state to see it
+fn options() -> SocketOptions {
+    SocketOptions {
+        min_delay: 50,
+        max_delay: 100,
+        ..Default::default()
+    }
+}
+
+#[wasm_bindgen_test]
+async fn send_and_recv() {
+    let socket = Socket::connect(ECHO_SERVER, options());
+    wait_state(&socket, SocketState::Open).await;
+
+    socket.send(&serde_json::json!({ "text": "hello" })).unwrap();
+    let message: serde_json::Value = socket.recv().await.unwrap().unwrap();
+    assert_eq!(message, serde_json::json!({ "text": "hello" }));
+
+    socket.send_bytes(&[1, 2, 3]).unwrap();
+    assert_eq!(socket.recv_message().await, Some(Message::Binary(vec![1, 2, 3])));
+
+    socket.close();
+    assert_eq!(socket.state(), SocketState::Closed);
+    assert_eq!(socket.recv_message().await, None);
+    assert_eq!(socket.send(&1), Err(SocketError::Closed));
+}
+
+#[wasm_bindgen_test]
+async fn buffers_before_open() {
+    let socket = Socket::connect(ECHO_SERVER, SocketOptions { buffer_limit: 2, ..options() });
+    assert_eq!(socket.state(), SocketState::Connecting);
+    socket.send_message(text("first")).unwrap();
+    socket.send_message(text("second")).unwrap();
+    assert_eq!(socket.send_message(text("third")), Err(SocketError::BufferFull));
+
+    assert_eq!(socket.recv_message().await, Some(text("first")));
+    assert_eq!(socket.recv_message().await, Some(text("second")));
+    assert_eq!(socket.state(), SocketState::Open);
+    socket.close();
+}
+
+#[wasm_bindgen_test]
+async fn reconnects_and_sends_the_buffer() {
+    let socket = Socket::connect(ECHO_SERVER, options());
+    wait_state(&socket, SocketState::Open).await;
+
+    //the server closes the connection
+    socket.send_message(text("close")).unwrap();
+    wait_state(&socket, SocketState::Reconnecting { attempt: 1 }).await;
+    socket.send_message(text("while offline")).unwrap();
+
+    wait_state(&socket, SocketState::Open).await;
+    assert_eq!(socket.recv_message().await, Some(text("while offline")));
+    socket.close();
+}
+
+#[wasm_bindgen_test]
+async fn invalid_url_is_closed() {
+    let socket = Socket::connect("not a url", options());
+    assert_eq!(socket.state(), SocketState::Closed);
+    assert_eq!(socket.recv_message().await, None);
+    assert_eq!(socket.send_message(text("lost")), Err(SocketError::Closed));
+}
+
+#[wasm_bindgen_test]
+async fn clones_recv_together() {
+    let socket = Socket::connect(ECHO_SERVER, options());
+    wait_state(&socket, SocketState::Open).await;
+
+    //every task waits for one message, a message goes to one of them
+    let received = Rc::new(RefCell::new(Vec::new()));
+    for _ in 0..3 {
+        let (socket, received) = (socket.clone(), received.clone());
+        spawn_local(async move {
+            let message = socket.recv_message().await;
+            received.borrow_mut().push(message);
+        });
+    }
+    sleep(20).await;
+    socket.send_message(text("a")).unwrap();
+    socket.send_message(text("b")).unwrap();
+    sleep(100).await;
+    let mut messages = received.borrow().clone();
+    messages.sort_by_key(|m| format!("{m:?}"));
+    assert_eq!(messages, vec![Some(text("a")), Some(text("b"))]);
+
+    //the last task gets None on close
+    socket.close();
+    sleep(20).await;
+    assert_eq!(received.borrow().len(), 3);
+    assert_eq!(received.borrow()[2], None);
+}
+
+#[wasm_bindgen_test]
+async fn heartbeat_without_reply() {
+    let socket = Socket::connect(ECHO_SERVER, SocketOptions { heartbeat: Some(20), ..options() });
+    wait_state(&socket, SocketState::Open).await;
+
+    //the echoes of "ping" are not given to recv
+    sleep(60).await;
+    socket.send_message(text("after the pings")).unwrap();
+    assert_eq!(socket.recv_message().await, Some(text("after the pings")));
+
+    //without heartbeat_reply a quiet server keeps the connection
+    socket.send_message(text("silent")).unwrap();
+    sleep(100).await;
+    assert_eq!(socket.state(), SocketState::Open);
+    socket.close();
+}
+
+#[wasm_bindgen_test]
+async fn heartbeat_reply_restarts_a_silent_connection() {
+    let options = SocketOptions {
+        heartbeat: Some(20),
+        heartbeat_reply: Some("pong".to_owned()),
+        ..options()
+    };
+    let socket = Socket::connect(ECHO_SERVER, options);
+    wait_state(&socket, SocketState::Open).await;
+
+    //the echoes of "ping" keep the connection, after "silent" it's restarted
+    sleep(60).await;
+    assert_eq!(socket.state(), SocketState::Open);
+    socket.send_message(text("silent")).unwrap();
+    wait_state(&socket, SocketState::Reconnecting { attempt: 1 }).await;
+    wait_state(&socket, SocketState::Open).await;
+    socket.close();
+}